@@ -0,0 +1,105 @@
+use std::io::Write;
+use chrono::{Duration, Local};
+use crate::config::{tr, trf};
+use crate::history::HistoryEvent;
+use crate::{Task, TasksManager};
+
+impl Task {
+    fn mark_archived(&mut self) {
+        self.stop_timer();
+        self.record(HistoryEvent::Archived);
+    }
+}
+
+impl TasksManager {
+    pub fn archive(&self) -> &Vec<Task> {
+        &self.projects[self.current].archive
+    }
+
+    fn archive_at(&mut self, index: usize) {
+        let mut task = self.tasks_mut().remove(index);
+        task.mark_archived();
+        self.projects[self.current].archive.push(task);
+    }
+
+    pub fn archive_task(&mut self, name: &str) -> Result<String, String> {
+        match self.find_task(name) {
+            Some(index) if self.tasks()[index].is_closed() => {
+                self.archive_at(index);
+                Ok(trf!("Задача \"{}\" перемещена в архив", "Task \"{}\" moved to archive", name))
+            }
+            Some(_) => Err(trf!("Задача \"{}\" ещё не выполнена", "Task \"{}\" is not completed yet", name)),
+            None => Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        }
+    }
+
+    pub fn archive_completed(&mut self, older_than_days: u32) -> usize {
+        let threshold = Local::now() - Duration::days(older_than_days as i64);
+        let mut archived = 0;
+
+        for project in 0..self.projects.len() {
+            let mut index = 0;
+            while index < self.projects[project].tasks.len() {
+                let task = &self.projects[project].tasks[index];
+                if task.is_closed() && task.completed_time.is_none_or(|time| time <= threshold) {
+                    let mut task = self.projects[project].tasks.remove(index);
+                    task.mark_archived();
+                    self.projects[project].archive.push(task);
+                    archived += 1;
+                } else {
+                    index += 1;
+                }
+            }
+        }
+
+        archived
+    }
+
+    pub fn auto_archive(&mut self) -> Option<String> {
+        let days = self.auto_archive_days?;
+        match self.archive_completed(days) {
+            0 => None,
+            archived => Some(trf!("Автоматически перемещено в архив задач: {}", "Tasks archived automatically: {}", archived)),
+        }
+    }
+
+    pub fn restore_task(&mut self, name: &str) -> Result<String, String> {
+        if self.find_task(name).is_some() {
+            return Err(trf!("Задача с именем \"{}\" уже есть в списке задач", "Task \"{}\" is already in the task list", name));
+        }
+
+        let archive = &mut self.projects[self.current].archive;
+        match archive.iter().position(|task| task.name == name) {
+            Some(index) => {
+                let mut task = archive.remove(index);
+                task.record(HistoryEvent::Restored);
+                self.tasks_mut().push(task);
+                Ok(trf!("Задача \"{}\" восстановлена из архива", "Task \"{}\" restored from archive", name))
+            }
+            None => Err(trf!("Задачи с именем \"{}\" нет в архиве", "Task \"{}\" is not in the archive", name)),
+        }
+    }
+
+    pub fn print_archive(&self, out: &mut impl Write, query: &str) -> std::io::Result<()> {
+        let query = query.to_lowercase();
+        let tasks: Vec<&Task> = self.archive()
+            .iter()
+            .filter(|task| {
+                task.name.to_lowercase().contains(&query) || task.description.to_lowercase().contains(&query)
+            })
+            .collect();
+
+        if tasks.is_empty() {
+            writeln!(out, "{}", tr("В архиве нет задач.", "No tasks in the archive."))?;
+            return Ok(());
+        }
+
+        for (index, task) in tasks.into_iter().enumerate() {
+            writeln!(out, "{}. ", index + 1)?;
+            task.print_task(out)?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::{Deserialize, Serialize};
+use crate::config::{self, tr, trf};
+use crate::{Task, TasksManager};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Attachment {
+    File { path: String, managed: bool },
+    Url { url: String },
+}
+
+fn managed_path(path: &str) -> PathBuf {
+    match &config::current().attachments_dir {
+        Some(dir) => dir.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+pub fn is_link(input: &str) -> bool {
+    input.contains("://") || input.starts_with("mailto:")
+}
+
+impl Attachment {
+    pub fn parse(input: &str, task_id: &str, copy: bool) -> Result<Self, String> {
+        if is_link(input) {
+            return Ok(Attachment::Url { url: input.to_owned() });
+        }
+
+        let dir = if copy {
+            match &config::current().attachments_dir {
+                Some(dir) => Some(dir.as_path()),
+                None => return Err(tr("Не задан каталог вложений, укажите параметр attachments_dir", "Attachments directory is not set, specify the attachments_dir setting").to_owned()),
+            }
+        } else {
+            None
+        };
+        Self::file(input, task_id, dir)
+    }
+
+    fn file(input: &str, task_id: &str, managed_dir: Option<&Path>) -> Result<Self, String> {
+        let source = Path::new(input);
+        if !source.is_file() {
+            return Err(trf!("Файл \"{}\" не существует", "File \"{}\" does not exist", input));
+        }
+
+        let dir = match managed_dir {
+            Some(dir) => dir,
+            None => {
+                let path = fs::canonicalize(source).map_err(|err| trf!("Ошибка при доступе к файлу \"{}\": {}", "Error accessing file \"{}\": {}", input, err))?;
+                return Ok(Attachment::File { path: path.to_string_lossy().into_owned(), managed: false });
+            }
+        };
+
+        let name = source.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let target = dir.join(task_id).join(&name);
+        if target.exists() {
+            return Err(trf!("Вложение \"{}\" уже есть в каталоге вложений задачи", "Attachment \"{}\" already exists in the task attachments directory", name));
+        }
+
+        Ok(Attachment::File { path: format!("{}/{}", task_id, name), managed: true })
+    }
+
+    pub fn copy_file(&self, input: &str) -> Result<(), String> {
+        match &config::current().attachments_dir {
+            Some(dir) => self.copy_into(input, dir),
+            None => Ok(()),
+        }
+    }
+
+    fn copy_into(&self, input: &str, dir: &Path) -> Result<(), String> {
+        if let Attachment::File { path, managed: true } = self {
+            let target = dir.join(path);
+            target
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::copy(input, &target))
+                .map_err(|err| trf!("Ошибка при копировании файла \"{}\": {}", "Error copying file \"{}\": {}", input, err))?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_copy(&self) {
+        if let Attachment::File { managed: true, .. } = self {
+            let _ = fs::remove_file(self.location());
+        }
+    }
+
+    pub fn location(&self) -> String {
+        match self {
+            Attachment::File { path, managed: true } => managed_path(path).to_string_lossy().into_owned(),
+            Attachment::File { path, managed: false } => path.clone(),
+            Attachment::Url { url } => url.clone(),
+        }
+    }
+
+    pub fn is_missing(&self) -> bool {
+        match self {
+            Attachment::File { .. } => !Path::new(&self.location()).exists(),
+            Attachment::Url { .. } => false,
+        }
+    }
+
+    pub fn open(&self) -> Result<String, String> {
+        let location = self.location();
+        if self.is_missing() {
+            return Err(trf!("Файл вложения \"{}\" не найден", "Attachment file \"{}\" not found", location));
+        }
+
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        } else if cfg!(target_os = "macos") {
+            Command::new("open")
+        } else {
+            Command::new("xdg-open")
+        };
+
+        command
+            .arg(&location)
+            .spawn()
+            .map_err(|err| trf!("Ошибка при открытии \"{}\": {}", "Error opening \"{}\": {}", location, err))?;
+        Ok(trf!("Открыто \"{}\"", "Opened \"{}\"", location))
+    }
+}
+
+impl fmt::Display for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attachment::File { managed: true, .. } => write!(f, "{} ({})", self.location(), config::tr("копия", "copy")),
+            _ => write!(f, "{}", self.location()),
+        }
+    }
+}
+
+impl Task {
+    pub fn attach(&mut self, attachment: Attachment) -> bool {
+        if self.attachments.contains(&attachment) {
+            return false;
+        }
+
+        let list = |attachments: &[Attachment]| attachments.iter().map(Attachment::location).collect::<Vec<_>>().join(", ");
+        let old = list(&self.attachments);
+        self.attachments.push(attachment);
+        self.record_edit("Вложения", old, list(&self.attachments));
+        true
+    }
+}
+
+impl TasksManager {
+    pub fn missing_attachments(&self) -> Vec<(&str, &Task, &Attachment)> {
+        let mut missing = vec![];
+
+        for project in &self.projects {
+            for task in project.tasks.iter().chain(&project.archive) {
+                for attachment in task.attachments.iter().filter(|attachment| attachment.is_missing()) {
+                    missing.push((project.name.as_str(), task, attachment));
+                }
+            }
+        }
+
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Priority;
+
+    #[test]
+    fn attaches_files_and_links_and_detects_missing_files() {
+        let dir = std::env::temp_dir().join(format!("task_manager_attachments_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("план.txt");
+        fs::write(&file, "план").unwrap();
+        let file = file.to_str().unwrap();
+
+        let mut manager = TasksManager::new();
+        let mut task = Task::new("a".to_owned(), String::new(), Priority::Low);
+        assert!(task.attach(Attachment::parse("https://example.com/doc", &task.id, false).unwrap()));
+        assert!(task.attach(Attachment::parse(file, &task.id, false).unwrap()));
+        assert!(!task.attach(Attachment::parse(file, &task.id, false).unwrap()));
+
+        let copy = Attachment::file(file, &task.id, Some(&dir.join("managed"))).unwrap();
+        assert!(copy == Attachment::File { path: format!("{}/план.txt", task.id), managed: true });
+        assert!(!dir.join("managed").join(&task.id).join("план.txt").exists());
+        copy.copy_into(file, &dir.join("managed")).unwrap();
+        assert!(dir.join("managed").join(&task.id).join("план.txt").exists());
+        assert!(Attachment::file(file, &task.id, Some(&dir.join("managed"))).is_err());
+        assert!(Attachment::parse("нет.txt", &task.id, false).is_err());
+
+        manager.add_task(task);
+        assert!(manager.missing_attachments().is_empty());
+        fs::remove_file(file).unwrap();
+        let missing = manager.missing_attachments();
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0].2.open().unwrap_err().contains("не найден"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
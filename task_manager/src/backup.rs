@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDateTime};
+use crate::config::{self, trf};
+use crate::{storage};
+
+const NAME_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+pub struct Backup {
+    pub path: PathBuf,
+    pub time: DateTime<Local>,
+}
+
+fn backup_dir(filename: &str) -> PathBuf {
+    PathBuf::from(format!("{}.backups", filename))
+}
+
+pub fn list(filename: &str) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = fs::read_dir(backup_dir(filename))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let stamp = name.strip_suffix(".bak")?;
+            let time = NaiveDateTime::parse_from_str(stamp, NAME_FORMAT).ok()?.and_local_timezone(Local).earliest()?;
+            Some(Backup { path: entry.path(), time })
+        })
+        .collect();
+
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    backups
+}
+
+fn create_with_limit(filename: &str, keep: usize) -> Result<(), String> {
+    if keep == 0 {
+        return Ok(());
+    }
+
+    let data = match fs::read(filename) {
+        Ok(data) if !data.is_empty() => data,
+        _ => return Ok(()),
+    };
+
+    let backups = list(filename);
+    if backups.first().is_some_and(|latest| fs::read(&latest.path).is_ok_and(|latest| latest == data)) {
+        return Ok(());
+    }
+
+    let dir = backup_dir(filename);
+    fs::create_dir_all(&dir).map_err(|err| trf!("Ошибка при создании резервной копии: {}", "Error creating backup: {}", err))?;
+
+    let mut time = Local::now();
+    if let Some(latest) = backups.first().filter(|latest| latest.time.format(NAME_FORMAT).to_string() >= time.format(NAME_FORMAT).to_string()) {
+        time = latest.time + chrono::Duration::milliseconds(1);
+    }
+    let path = dir.join(format!("{}.bak", time.format(NAME_FORMAT)));
+    fs::write(&path, data).map_err(|err| trf!("Ошибка при создании резервной копии: {}", "Error creating backup: {}", err))?;
+
+    for old in list(filename).into_iter().skip(keep) {
+        let _ = fs::remove_file(old.path);
+    }
+    Ok(())
+}
+
+pub fn create(filename: &str) -> Result<(), String> {
+    create_with_limit(filename, config::current().backups)
+}
+
+pub fn find(filename: &str, number: &str) -> Result<Backup, String> {
+    let mut backups = list(filename);
+    let count = backups.len();
+    match number.parse::<usize>() {
+        Ok(number) if (1..=count).contains(&number) => Ok(backups.swap_remove(number - 1)),
+        _ => Err(trf!("Резервной копии с номером \"{}\" нет, всего копий: {}", "No backup with number \"{}\", total backups: {}", number, count)),
+    }
+}
+
+pub fn restore(filename: &str, backup: &Backup) -> Result<String, String> {
+    create_with_limit(filename, config::current().backups.max(1))?;
+    let data = fs::read(&backup.path).map_err(|err| trf!("Ошибка при восстановлении из резервной копии: {}", "Error restoring from backup: {}", err))?;
+    storage::write_locked(filename, data, None)?;
+    Ok(trf!("Файл \"{}\" восстановлен из копии от {}", "File \"{}\" restored from backup of {}", filename, backup.time.format(&config::current().date_format)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_skips_duplicates() {
+        let file = std::env::temp_dir().join(format!("task_manager_backup_{}.json", std::process::id()));
+        let file = file.to_str().unwrap();
+
+        for version in 0..5 {
+            fs::write(file, format!("версия {}", version)).unwrap();
+            create_with_limit(file, 3).unwrap();
+            create_with_limit(file, 3).unwrap();
+        }
+
+        let backups = list(file);
+        assert_eq!(backups.len(), 3);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), "версия 4");
+        assert_eq!(fs::read_to_string(&find(file, "3").unwrap().path).unwrap(), "версия 2");
+        assert!(find(file, "4").is_err());
+
+        fs::write(file, "испорчено").unwrap();
+        restore(file, &backups[1]).unwrap();
+        assert_eq!(fs::read_to_string(file).unwrap(), "версия 3");
+        assert_eq!(fs::read_to_string(&list(file)[0].path).unwrap(), "испорчено");
+
+        let _ = fs::remove_dir_all(backup_dir(file));
+        let _ = fs::remove_file(file);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::config::trf;
+use crate::filter::Filter;
+use crate::{sort_for_display, Hooks, Priority, Task, TasksManager};
+
+pub enum BulkAction {
+    Complete,
+    Delete,
+    SetPriority(Priority),
+    AddTag(String),
+}
+
+fn parse_numbers(selection: &str, count: usize) -> Option<Result<Vec<usize>, String>> {
+    let parts: Vec<&str> = selection
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if parts.is_empty() || !parts.iter().all(|part| match part.split_once('-') {
+        Some((from, to)) => is_number(from) && is_number(to),
+        None => is_number(part),
+    }) {
+        return None;
+    }
+
+    let mut numbers = vec![];
+    for part in parts {
+        let (from, to) = match part.split_once('-') {
+            Some((from, to)) => (from.parse::<usize>().unwrap_or(0), to.parse::<usize>().unwrap_or(0)),
+            None => {
+                let number = part.parse::<usize>().unwrap_or(0);
+                (number, number)
+            }
+        };
+
+        if from == 0 || to > count || from > to {
+            return Some(Err(trf!("Неправильный номер или диапазон \"{}\", всего задач: {}", "Invalid number or range \"{}\", total tasks: {}", part, count)));
+        }
+
+        for number in from..=to {
+            if !numbers.contains(&number) {
+                numbers.push(number);
+            }
+        }
+    }
+
+    Some(Ok(numbers))
+}
+
+impl Task {
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        if self.tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+            return false;
+        }
+
+        let old = self.tags.join(" ");
+        self.tags.push(tag.to_owned());
+        self.record_edit("Теги", old, self.tags.join(" "));
+        true
+    }
+}
+
+impl TasksManager {
+    pub fn select(&self, selection: &str) -> Result<Vec<(usize, &Task)>, String> {
+        let sorted = sort_for_display(self.tasks());
+
+        match parse_numbers(selection, sorted.len()) {
+            Some(numbers) => Ok(numbers?.into_iter().map(|number| (number, sorted[number - 1])).collect()),
+            None => {
+                let filter = Filter::parse(selection)?;
+                Ok(sorted
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, task)| filter.matches(task))
+                    .map(|(index, task)| (index + 1, task))
+                    .collect())
+            }
+        }
+    }
+
+    pub fn apply_bulk(&mut self, hooks: &Hooks, ids: &[String], action: &BulkAction) -> Vec<Result<String, String>> {
+        ids.iter()
+            .map(|id| {
+                let index = match self.tasks().iter().position(|task| task.id == *id) {
+                    Some(index) => index,
+                    None => return Err(trf!("Задача с идентификатором {} не существует", "Task with id {} does not exist", id)),
+                };
+
+                match action {
+                    BulkAction::Complete => self.complete_at_with_hooks(hooks, index),
+                    BulkAction::Delete => self.remove_at_with_hooks(hooks, index),
+                    BulkAction::SetPriority(priority) => {
+                        let mut updated = self.tasks()[index].clone();
+                        updated.priority = priority.clone();
+                        self.edit_at_with_hooks(hooks, index, updated)
+                    }
+                    BulkAction::AddTag(tag) => self.tag_at_with_hooks(hooks, index, tag),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,841 @@
+use std::io::Write;
+use std::path::Path;
+use chrono::Local;
+use serde::Serialize;
+use crate::crdt::{ChangeSet, Replica};
+use crate::{backup, diff};
+use crate::sync::{GitSync, Pull, Side};
+use crate::config::{self, tr, trf};
+use crate::{crypto, quickadd, reminders, table, users, Hooks, Priority, Task, TasksManager};
+
+const PASSPHRASE_ENV: &str = "TASK_MANAGER_PASSPHRASE";
+
+const USAGE: &str = "Использование: task_manager [--file <файл>] [--user <пользователь>] <команда> [аргументы] [--json]
+
+Команды:
+  list [--all] [--project <проект>] [--assignee <пользователь> | --mine]
+                                         вывести задачи
+  find <имя|номер> [--project <проект>]  найти задачу
+  add <имя> [--description <текст>] [--priority high|medium|low]
+  quick <строка>                         быстро добавить задачу: Купить молоко !high #дом due:tomorrow \"описание\"
+  edit <имя|номер> [--name <имя>] [--description <текст>] [--priority high|medium|low]
+  assign <имя|номер> [<исполнитель>]     назначить исполнителя (без имени - снять)
+  remove <имя|номер>                     удалить задачу
+  attach <имя|номер> <путь|url> [--copy] прикрепить файл (--copy - скопировать в каталог вложений) или ссылку
+  attachments [<имя|номер>] [--missing]  вывести вложения задачи или отсутствующие файлы вложений
+  open <имя|номер> <n>                   открыть вложение задачи
+  note <имя|номер> <текст>               добавить заметку к задаче
+  complete <имя|номер>                   отметить задачу как выполненную
+  report                                 отчёт о продуктивности
+  sync [--ours | --theirs]               синхронизировать задачи через git (при конфликте
+                                         оставить свою версию или версию из удалённого репозитория)
+  diff <файл> [<файл>]                   сравнить два файла с задачами (без второго - с текущим файлом)
+  backups [list | diff <n> | restore <n>] резервные копии файла: список, отличия от текущего
+                                         состояния и восстановление
+  compact                                сжать журнал изменений до одного снимка
+  changes export [<файл>] [--since <n>]  выгрузить изменения для синхронизации без сети
+  changes import <файл>                  применить изменения из другой копии
+  watch [<файл>] [--interval <с>] [--lead <мин>] [--sink stdout|file:<путь>|command:<команда>] [--once]
+
+Без команды запускается интерактивное меню.";
+
+const USAGE_EN: &str = "Usage: task_manager [--file <file>] [--user <user>] <command> [arguments] [--json]
+
+Commands:
+  list [--all] [--project <project>] [--assignee <user> | --mine]
+                                         list tasks
+  find <name|number> [--project <project>]
+                                         find a task
+  add <name> [--description <text>] [--priority high|medium|low]
+  quick <line>                           quick-add a task: Buy milk !high #home due:tomorrow \"description\"
+  edit <name|number> [--name <name>] [--description <text>] [--priority high|medium|low]
+  assign <name|number> [<assignee>]      assign a task (without a name - unassign)
+  remove <name|number>                   remove a task
+  attach <name|number> <path|url> [--copy]
+                                         attach a file (--copy - copy it to the attachments directory) or a link
+  attachments [<name|number>] [--missing]
+                                         list task attachments or missing attachment files
+  open <name|number> <n>                 open a task attachment
+  note <name|number> <text>              add a note to a task
+  complete <name|number>                 mark a task as completed
+  report                                 productivity report
+  sync [--ours | --theirs]               sync tasks through git (on conflict keep
+                                         our version or the version from the remote repository)
+  diff <file> [<file>]                   compare two task files (without the second - with the current file)
+  backups [list | diff <n> | restore <n>] file backups: list, differences from the current
+                                         state and restore
+  compact                                compact the change journal into a single snapshot
+  changes export [<file>] [--since <n>]  export changes for offline sync
+  changes import <file>                  apply changes from another copy
+  watch [<file>] [--interval <s>] [--lead <min>] [--sink stdout|file:<path>|command:<command>] [--once]
+
+Without a command the interactive menu starts.";
+
+#[derive(Serialize, Debug)]
+struct CliError {
+    code: &'static str,
+    message: String,
+}
+
+impl CliError {
+    fn new(code: &'static str, message: String) -> Self {
+        Self { code, message }
+    }
+
+    fn usage(message: String) -> Self {
+        Self::new("usage", message)
+    }
+
+    fn output(err: std::io::Error) -> Self {
+        Self::new("output", trf!("Ошибка при выводе: {}", "Output error: {}", err))
+    }
+
+    fn exit_code(&self) -> i32 {
+        if self.code == "usage" { 2 } else { 1 }
+    }
+}
+
+#[derive(Serialize)]
+struct ProjectTask<'a> {
+    project: &'a str,
+    #[serde(flatten)]
+    task: &'a Task,
+}
+
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    json: bool,
+    all: bool,
+    mine: bool,
+    ours: bool,
+    theirs: bool,
+    copy: bool,
+    missing: bool,
+    assignee: Option<String>,
+    since: Option<String>,
+    project: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    priority: Option<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut parsed = Args::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::usage(trf!("Не указано значение параметра \"{}\"", "No value given for parameter \"{}\"", name)))
+            };
+
+            match arg.as_str() {
+                "--json" => parsed.json = true,
+                "--all" => parsed.all = true,
+                "--mine" => parsed.mine = true,
+                "--ours" => parsed.ours = true,
+                "--theirs" => parsed.theirs = true,
+                "--copy" => parsed.copy = true,
+                "--missing" => parsed.missing = true,
+                "--assignee" => parsed.assignee = Some(value(arg)?),
+                "--since" => parsed.since = Some(value(arg)?),
+                "--project" => parsed.project = Some(value(arg)?),
+                "--name" => parsed.name = Some(value(arg)?),
+                "--description" => parsed.description = Some(value(arg)?),
+                "--priority" => parsed.priority = Some(value(arg)?),
+                _ if arg.starts_with("--") => {
+                    return Err(CliError::usage(trf!("Неизвестный параметр \"{}\"", "Unknown parameter \"{}\"", arg)));
+                }
+                _ => parsed.positional.push(arg.clone()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn task_name(&self) -> Result<&str, CliError> {
+        match self.positional.first() {
+            Some(name) => Ok(name),
+            None => Err(CliError::usage(tr("Не указано имя задачи", "Task name is not specified").to_owned())),
+        }
+    }
+
+    fn priority(&self) -> Result<Option<Priority>, CliError> {
+        match &self.priority {
+            Some(priority) => Priority::from_name(priority)
+                .map(Some)
+                .ok_or_else(|| CliError::usage(trf!("Неизвестный приоритет \"{}\"", "Unknown priority \"{}\"", priority))),
+            None => Ok(None),
+        }
+    }
+}
+
+fn read_passphrase(filename: &str) -> Result<Option<String>, CliError> {
+    if !crypto::is_encrypted_file(filename) {
+        return Ok(None);
+    }
+
+    match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => Ok(Some(passphrase)),
+        Err(_) => crate::prompt_password(&trf!("Файл \"{}\" зашифрован, введите пароль: ", "File \"{}\" is encrypted, enter password: ", filename))
+            .map(Some)
+            .map_err(|err| CliError::new("input", err.to_string())),
+    }
+}
+
+fn data_file() -> Result<String, CliError> {
+    config::current().data_file.clone().ok_or_else(|| {
+        CliError::usage(tr("Не указан файл с задачами, используйте --file или параметр data_file", "Task file is not specified, use --file or the data_file setting").to_owned())
+    })
+}
+
+struct Store {
+    filename: String,
+    passphrase: Option<String>,
+    manager: TasksManager,
+    sync: Option<GitSync>,
+}
+
+impl Store {
+    fn open(args: &Args) -> Result<Self, CliError> {
+        Self::open_file(data_file()?, args)
+    }
+
+    fn open_file(filename: String, args: &Args) -> Result<Self, CliError> {
+        let mut passphrase = None;
+        let mut manager = TasksManager::new();
+
+        if Path::new(&filename).exists() {
+            passphrase = read_passphrase(&filename)?;
+            manager
+                .read_from_file(&filename, passphrase.as_deref())
+                .map_err(|msg| CliError::new("storage", msg))?;
+        }
+
+        let sync = match config::current().sync {
+            config::SyncMode::Git => Some(GitSync::open(&filename, &manager).map_err(|msg| CliError::new("sync", msg))?),
+            config::SyncMode::None => None,
+        };
+
+        if let Some(project) = &args.project {
+            manager.switch_project(project).map_err(|msg| CliError::new("not_found", msg))?;
+        }
+
+        Ok(Self { filename, passphrase, manager, sync })
+    }
+
+    fn save(&mut self) -> Result<(), CliError> {
+        let result = match &mut self.sync {
+            Some(sync) => sync.commit(&mut self.manager).map(|_| ()),
+            None => self.manager.store_to_file(&self.filename, self.passphrase.as_deref()).map(|_| ()),
+        };
+        result.map_err(|msg| CliError::new("storage", msg))
+    }
+
+    fn find(&self, name: &str) -> Result<usize, CliError> {
+        self.manager.resolve_task(name).map_err(|msg| CliError::new("not_found", msg))
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            let _ = writeln!(std::io::stdout(), "{}", json);
+        }
+        Err(err) => eprintln!("{}", trf!("Ошибка при формировании JSON: {}", "Error building JSON: {}", err)),
+    }
+}
+
+fn print_task_result(args: &Args, store: &Store, task: &Task, message: &str) {
+    if args.json {
+        print_json(&ProjectTask { project: store.manager.current_project(), task });
+    } else {
+        println!("{}", message);
+    }
+}
+
+fn list(args: &Args) -> Result<(), CliError> {
+    let store = Store::open(args)?;
+    let manager = &store.manager;
+
+    let projects: Vec<_> = if args.all {
+        manager.projects.iter().collect()
+    } else {
+        vec![&manager.projects[manager.current]]
+    };
+
+    let assignee = match (&args.assignee, args.mine) {
+        (Some(assignee), _) => Some(assignee.as_str()),
+        (None, true) => Some(users::current_user().ok_or_else(|| {
+            CliError::usage(tr("Текущий пользователь не задан, используйте --user или параметр user", "Current user is not set, use --user or the user setting").to_owned())
+        })?),
+        (None, false) => None,
+    };
+
+    let mut tasks = vec![];
+    for project in projects {
+        for (number, name, task) in project.numbered_tasks() {
+            if assignee.is_none_or(|assignee| task.is_assigned_to(assignee)) {
+                tasks.push((number, name, task));
+            }
+        }
+    }
+
+    if args.json {
+        let tasks: Vec<ProjectTask> = tasks.iter().map(|(_, project, task)| ProjectTask { project, task }).collect();
+        print_json(&tasks);
+    } else {
+        table::print_table(&mut std::io::stdout(), &tasks, args.all).map_err(CliError::output)?;
+    }
+
+    Ok(())
+}
+
+fn find(args: &Args) -> Result<(), CliError> {
+    let store = Store::open(args)?;
+    let task = &store.manager.tasks()[store.find(args.task_name()?)?];
+
+    if args.json {
+        print_json(&ProjectTask { project: store.manager.current_project(), task });
+    } else {
+        task.print_task(&mut std::io::stdout()).map_err(CliError::output)?;
+    }
+
+    Ok(())
+}
+
+fn add(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let name = args.task_name()?;
+
+    if store.manager.find_task(name).is_some() {
+        return Err(CliError::new("exists", trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", name)));
+    }
+
+    let priority = args.priority()?.unwrap_or_else(|| config::current().default_priority.clone());
+    let task = Task::new(name.to_owned(), args.description.clone().unwrap_or_default(), priority);
+    let message = store.manager.add_task_with_hooks(hooks, task).map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    if let Some(task) = store.manager.tasks().last() {
+        print_task_result(args, &store, task, &message);
+    }
+    Ok(())
+}
+
+fn quick(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let task = quickadd::parse_quick_add(&args.positional.join(" "), Local::now()).map_err(CliError::usage)?;
+
+    if store.manager.find_task(&task.name).is_some() {
+        return Err(CliError::new("exists", trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", task.name)));
+    }
+
+    let message = store.manager.add_task_with_hooks(hooks, task).map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    if let Some(task) = store.manager.tasks().last() {
+        print_task_result(args, &store, task, &message);
+    }
+    Ok(())
+}
+
+fn edit(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let task = &store.manager.tasks()[store.find(args.task_name()?)?];
+    let name = task.name.clone();
+
+    let updated = Task::new(
+        args.name.clone().unwrap_or_else(|| task.name.clone()),
+        args.description.clone().unwrap_or_else(|| task.description.clone()),
+        args.priority()?.unwrap_or_else(|| task.priority.clone()),
+    );
+    let new_name = updated.name.clone();
+    if new_name != name && store.manager.find_task(&new_name).is_some() {
+        return Err(CliError::new("exists", trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", new_name)));
+    }
+
+    let message = store.manager
+        .edit_task_with_hooks(hooks, &name, updated)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    let index = store.manager.find_task(&new_name).unwrap_or(store.manager.tasks().len() - 1);
+    print_task_result(args, &store, &store.manager.tasks()[index], &message);
+    Ok(())
+}
+
+fn assign(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let index = store.find(args.task_name()?)?;
+    let name = store.manager.tasks()[index].name.clone();
+    let assignee = args.positional.get(1).cloned();
+
+    let message = store.manager
+        .assign_task_with_hooks(hooks, &name, assignee)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    print_task_result(args, &store, &store.manager.tasks()[index], &message);
+    Ok(())
+}
+
+fn attach(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let index = store.find(args.task_name()?)?;
+    let name = store.manager.tasks()[index].name.clone();
+    let input = args
+        .positional
+        .get(1)
+        .ok_or_else(|| CliError::usage(tr("Не указан путь к файлу или URL", "File path or URL is not specified").to_owned()))?;
+
+    let attached = store.manager.tasks()[index].attachments.clone();
+    let message = store.manager
+        .attach_with_hooks(hooks, &name, input, args.copy)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    if let Err(err) = store.save() {
+        for attachment in store.manager.tasks()[index].attachments.iter().filter(|attachment| !attached.contains(attachment)) {
+            attachment.remove_copy();
+        }
+        return Err(err);
+    }
+
+    print_task_result(args, &store, &store.manager.tasks()[index], &message);
+    Ok(())
+}
+
+fn note(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let index = store.find(args.task_name()?)?;
+    let name = store.manager.tasks()[index].name.clone();
+    let text = args.positional[1..].join(" ");
+
+    let message = store.manager
+        .add_note_with_hooks(hooks, &name, &text)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    print_task_result(args, &store, &store.manager.tasks()[index], &message);
+    Ok(())
+}
+
+fn attachments(args: &Args) -> Result<(), CliError> {
+    let store = Store::open(args)?;
+
+    if args.missing || args.positional.is_empty() {
+        let missing = store.manager.missing_attachments();
+        if args.json {
+            let missing: Vec<_> = missing
+                .iter()
+                .map(|(project, task, attachment)| {
+                    serde_json::json!({ "project": project, "task": task.name, "location": attachment.location() })
+                })
+                .collect();
+            print_json(&missing);
+        } else if missing.is_empty() {
+            println!("{}", tr("Все файлы вложений на месте", "All attachment files are in place"));
+        } else {
+            println!("{}", tr("Не найдены файлы вложений:", "Missing attachment files:"));
+            for (project, task, attachment) in missing {
+                println!("{}", trf!("  проект \"{}\", задача \"{}\": {}", "  project \"{}\", task \"{}\": {}", project, task.name, attachment.location()));
+            }
+        }
+        return Ok(());
+    }
+
+    let task = &store.manager.tasks()[store.find(args.task_name()?)?];
+    if args.json {
+        let attachments: Vec<_> = task
+            .attachments
+            .iter()
+            .enumerate()
+            .map(|(index, attachment)| {
+                serde_json::json!({
+                    "number": index + 1,
+                    "attachment": attachment,
+                    "location": attachment.location(),
+                    "missing": attachment.is_missing(),
+                })
+            })
+            .collect();
+        print_json(&attachments);
+    } else if task.attachments.is_empty() {
+        println!("{}", trf!("У задачи \"{}\" нет вложений", "Task \"{}\" has no attachments", task.name));
+    } else {
+        for (index, attachment) in task.attachments.iter().enumerate() {
+            let missing = if attachment.is_missing() { tr(" (файл не найден)", " (file not found)") } else { "" };
+            println!("{}. {}{}", index + 1, attachment, missing);
+        }
+    }
+    Ok(())
+}
+
+fn open(args: &Args) -> Result<(), CliError> {
+    let store = Store::open(args)?;
+    let task = &store.manager.tasks()[store.find(args.task_name()?)?];
+    let number = args.positional.get(1).ok_or_else(|| CliError::usage(tr("Не указан номер вложения", "Attachment number is not specified").to_owned()))?;
+
+    let attachment = number
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_sub(1))
+        .and_then(|index| task.attachments.get(index))
+        .ok_or_else(|| CliError::new("not_found", trf!("У задачи \"{}\" нет вложения с номером {}", "Task \"{}\" has no attachment number {}", task.name, number)))?;
+
+    let message = attachment.open().map_err(|msg| CliError::new("attachment", msg))?;
+    if args.json {
+        print_json(&serde_json::json!({ "message": message }));
+    } else {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+fn remove(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let task = store.manager.tasks()[store.find(args.task_name()?)?].clone();
+
+    let message = store.manager
+        .remove_task_with_hooks(hooks, &task.name)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    print_task_result(args, &store, &task, &message);
+    Ok(())
+}
+
+fn complete(args: &Args, hooks: &Hooks) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let index = store.find(args.task_name()?)?;
+    let name = store.manager.tasks()[index].name.clone();
+
+    let message = store.manager
+        .complete_task_with_hooks(hooks, &name)
+        .map_err(|msg| CliError::new("rejected", msg))?;
+    store.save()?;
+
+    print_task_result(args, &store, &store.manager.tasks()[index], &message);
+    Ok(())
+}
+
+fn read_side(conflict: &str) -> Result<Side, CliError> {
+    eprintln!("{}", conflict);
+    loop {
+        eprint!("{}", tr("Оставить версию (1 - нашу, 2 - из удалённого репозитория): ", "Keep version (1 - ours, 2 - from the remote repository): "));
+        let mut buffer = String::new();
+        match std::io::stdin().read_line(&mut buffer) {
+            Ok(0) | Err(_) => {
+                return Err(CliError::new("conflict", tr("Конфликт не разрешён, используйте --ours или --theirs", "Conflict is not resolved, use --ours or --theirs").to_owned()))
+            }
+            Ok(_) => match buffer.trim() {
+                "1" => return Ok(Side::Ours),
+                "2" => return Ok(Side::Theirs),
+                _ => eprintln!("{}", tr("Неверный ввод", "Invalid input")),
+            },
+        }
+    }
+}
+
+fn sync(args: &Args) -> Result<(), CliError> {
+    if args.ours && args.theirs {
+        return Err(CliError::usage(tr("Параметры --ours и --theirs нельзя указывать вместе", "--ours and --theirs cannot be used together").to_owned()));
+    }
+
+    let mut store = Store::open(args)?;
+    let mut sync = store.sync.take().ok_or_else(|| {
+        CliError::usage(tr("Синхронизация не включена, укажите --sync git или параметр sync", "Sync is not enabled, use --sync git or the sync setting").to_owned())
+    })?;
+    let error = |msg| CliError::new("sync", msg);
+
+    let (message, conflicts) = match sync.pull(&mut store.manager).map_err(error)? {
+        Pull::NoUpstream => (tr("Удалённая ветка не настроена, изменения отправлены в origin", "No upstream branch, changes pushed to origin").to_owned(), vec![]),
+        Pull::UpToDate => (tr("Новых изменений в удалённом репозитории нет", "No new changes in the remote repository").to_owned(), vec![]),
+        Pull::FastForward => (tr("Получены изменения из удалённого репозитория", "Received changes from the remote repository").to_owned(), vec![]),
+        Pull::Merge(mut merge) => {
+            let mut conflicts = vec![];
+            for index in 0..merge.conflicts.len() {
+                let conflict = &merge.conflicts[index];
+                conflicts.push(serde_json::json!({ "task": conflict.name, "fields": conflict.fields }));
+                let side = match (args.ours, args.theirs) {
+                    (true, _) => Side::Ours,
+                    (_, true) => Side::Theirs,
+                    _ => read_side(&conflict.describe())?,
+                };
+                merge.resolve(index, side);
+            }
+            (sync.finish(&mut store.manager, merge).map_err(error)?, conflicts)
+        }
+    };
+    sync.push().map_err(error)?;
+
+    if args.json {
+        print_json(&serde_json::json!({ "message": message, "conflicts": conflicts }));
+    } else {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+fn open_replica(store: &Store) -> Result<Replica, CliError> {
+    if store.passphrase.is_some() {
+        return Err(CliError::new("storage", tr("Журнал изменений не поддерживает зашифрованные файлы", "The change journal does not support encrypted files").to_owned()));
+    }
+    let mut replica = Replica::load(&store.filename).map_err(|msg| CliError::new("storage", msg))?;
+    replica.record(&store.manager).map_err(|msg| CliError::new("storage", msg))?;
+    Ok(replica)
+}
+
+fn changes(args: &Args) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    let mut replica = open_replica(&store)?;
+
+    let (count, message) = match (args.positional.first().map(String::as_str), args.positional.get(1)) {
+        (Some("export"), file) => {
+            let since = match &args.since {
+                Some(since) => since.parse().map_err(|_| CliError::usage(trf!("Неправильное значение --since \"{}\"", "Invalid --since value \"{}\"", since)))?,
+                None => 0,
+            };
+            let set = replica.export(since);
+            let count = set.changes.len();
+            let data = serde_json::to_string_pretty(&set).map_err(|err| CliError::new("output", err.to_string()))?;
+
+            match file {
+                Some(file) => {
+                    std::fs::write(file, data)
+                        .map_err(|err| CliError::new("storage", trf!("Ошибка при записи в файл \"{}\": {}", "Error writing file \"{}\": {}", file, err)))?;
+                }
+                None => println!("{}", data),
+            }
+            (count, trf!("Выгружено изменений: {}, часы реплики: {}", "Changes exported: {}, replica clock: {}", count, replica.clock()))
+        }
+        (Some("import"), Some(file)) => {
+            let data = std::fs::read(file)
+                .map_err(|err| CliError::new("storage", trf!("Ошибка при чтении файла \"{}\": {}", "Error reading file \"{}\": {}", file, err)))?;
+            let set: ChangeSet = serde_json::from_slice(&data)
+                .map_err(|err| CliError::new("storage", trf!("Ошибка при чтении файла \"{}\": {}", "Error reading file \"{}\": {}", file, err)))?;
+
+            let count = replica.import(set);
+            let project = store.manager.current_project().to_owned();
+            let mut manager = replica.to_manager().map_err(|msg| CliError::new("storage", msg))?;
+            manager.file_state = store.manager.file_state.take();
+            let _ = manager.switch_project(&project);
+            store.manager = manager;
+            store.save()?;
+            (count, trf!("Применено изменений: {}", "Changes applied: {}", count))
+        }
+        (Some("import"), None) => return Err(CliError::usage(tr("Не указан файл с изменениями", "Changes file is not specified").to_owned())),
+        _ => return Err(CliError::usage(tr("Ожидается changes export или changes import", "Expected changes export or changes import").to_owned())),
+    };
+    replica.save(&store.filename).map_err(|msg| CliError::new("storage", msg))?;
+
+    if !args.json {
+        eprintln!("{}", message);
+    } else if args.positional.len() > 1 {
+        print_json(&serde_json::json!({ "changes": count, "clock": replica.clock() }));
+    }
+    Ok(())
+}
+
+fn diff(args: &Args) -> Result<(), CliError> {
+    let load = |filename: &str| {
+        let mut manager = TasksManager::new();
+        manager
+            .read_from_file(filename, read_passphrase(filename)?.as_deref())
+            .map_err(|msg| CliError::new("storage", msg))?;
+        Ok::<_, CliError>(manager)
+    };
+
+    let (old, new) = match args.positional.as_slice() {
+        [old, new] => (load(old)?, load(new)?),
+        [old] => (load(old)?, Store::open(args)?.manager),
+        [] => return Err(CliError::usage(tr("Не указаны файлы для сравнения", "Files to compare are not specified").to_owned())),
+        _ => return Err(CliError::usage(tr("Ожидается не больше двух файлов", "At most two files are expected").to_owned())),
+    };
+
+    let changes = diff::diff(&old, &new).map_err(|msg| CliError::new("storage", msg))?;
+    if args.json {
+        print_json(&changes);
+    } else {
+        diff::print_diff(&mut std::io::stdout(), &changes).map_err(CliError::output)?;
+    }
+    Ok(())
+}
+
+fn backups(args: &Args) -> Result<(), CliError> {
+    manage_backups(&data_file()?, args)
+}
+
+fn manage_backups(filename: &str, args: &Args) -> Result<(), CliError> {
+    let number = || args.positional.get(1).map(String::as_str).ok_or_else(|| CliError::usage(tr("Не указан номер резервной копии", "Backup number is not specified").to_owned()));
+    let not_found = |msg| CliError::new("not_found", msg);
+
+    match args.positional.first().map(String::as_str) {
+        None | Some("list") => {
+            let backups = backup::list(filename);
+            if args.json {
+                let backups: Vec<_> = backups
+                    .iter()
+                    .enumerate()
+                    .map(|(index, backup)| serde_json::json!({ "number": index + 1, "time": backup.time, "path": backup.path }))
+                    .collect();
+                print_json(&backups);
+            } else if backups.is_empty() {
+                println!("{}", tr("Резервных копий нет", "No backups"));
+            } else {
+                for (index, backup) in backups.iter().enumerate() {
+                    println!("{}. {}", index + 1, backup.time.format(&config::current().date_format));
+                }
+            }
+        }
+        Some("diff") => {
+            let store = Store::open_file(filename.to_owned(), args)?;
+            let backup = backup::find(filename, number()?).map_err(not_found)?;
+            let data = std::fs::read(&backup.path)
+                .map_err(|err| CliError::new("storage", trf!("Ошибка при чтении резервной копии: {}", "Error reading backup: {}", err)))?;
+            let old = TasksManager::deserialize(data, &backup.path.to_string_lossy(), store.passphrase.as_deref())
+                .map_err(|msg| CliError::new("storage", msg))?;
+            let changes = diff::diff(&old, &store.manager).map_err(|msg| CliError::new("storage", msg))?;
+
+            if args.json {
+                print_json(&changes);
+            } else {
+                diff::print_diff(&mut std::io::stdout(), &changes).map_err(CliError::output)?;
+            }
+        }
+        Some("restore") => {
+            let backup = backup::find(filename, number()?).map_err(not_found)?;
+            let message = backup::restore(filename, &backup).map_err(|msg| CliError::new("storage", msg))?;
+            if args.json {
+                print_json(&serde_json::json!({ "message": message }));
+            } else {
+                println!("{}", message);
+            }
+        }
+        Some(other) => return Err(CliError::usage(trf!("Неизвестное действие \"{}\", доступны list, diff и restore", "Unknown action \"{}\", available: list, diff and restore", other))),
+    }
+    Ok(())
+}
+
+fn compact(args: &Args) -> Result<(), CliError> {
+    let mut store = Store::open(args)?;
+    if store.passphrase.is_some() {
+        return Err(CliError::new("storage", tr("Журнал изменений не поддерживает шифрование", "The change journal does not support encryption").to_owned()));
+    }
+
+    let message = store.manager.compact_file(&store.filename).map_err(|msg| CliError::new("storage", msg))?;
+    if args.json {
+        print_json(&serde_json::json!({ "message": message }));
+    } else {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+fn report(args: &Args) -> Result<(), CliError> {
+    let store = Store::open(args)?;
+    let report = store.manager.report();
+
+    if args.json {
+        print_json(&report);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return 0,
+    };
+
+    if command == "watch" {
+        return match reminders::WatchOptions::from_args(rest) {
+            Ok(options) => {
+                reminders::watch(options);
+                0
+            }
+            Err(msg) => {
+                eprintln!("{}", msg);
+                2
+            }
+        };
+    }
+
+    if command == "help" || command == "--help" {
+        println!("{}", tr(USAGE, USAGE_EN));
+        return 0;
+    }
+
+    let json = rest.iter().any(|arg| arg == "--json");
+    let hooks = Hooks::new(config::current().hooks_dir.clone());
+
+    let result = Args::parse(rest).and_then(|args| match command {
+        "list" => list(&args),
+        "find" => find(&args),
+        "add" => add(&args, &hooks),
+        "quick" => quick(&args, &hooks),
+        "edit" => edit(&args, &hooks),
+        "assign" => assign(&args, &hooks),
+        "remove" => remove(&args, &hooks),
+        "attach" => attach(&args, &hooks),
+        "attachments" => attachments(&args),
+        "open" => open(&args),
+        "note" => note(&args, &hooks),
+        "complete" => complete(&args, &hooks),
+        "report" => report(&args),
+        "sync" => sync(&args),
+        "changes" => changes(&args),
+        "compact" => compact(&args),
+        "backups" => backups(&args),
+        "diff" => diff(&args),
+        _ => Err(CliError::usage(trf!("Неизвестная команда \"{}\"\n\n{}", "Unknown command \"{}\"\n\n{}", command, tr(USAGE, USAGE_EN)))),
+    });
+
+    match result {
+        Ok(_) => 0,
+        Err(err) => {
+            if json {
+                print_json(&serde_json::json!({ "error": err }));
+            } else {
+                eprintln!("{}", err.message);
+            }
+            err.exit_code()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+
+    #[test]
+    fn restores_backup_over_corrupted_file() {
+        let file = std::env::temp_dir().join(format!("task_manager_cli_backups_{}.json", std::process::id()));
+        let file = file.to_str().unwrap();
+        let _ = fs::remove_file(file);
+
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        manager.store_to_file(file, None).unwrap();
+        manager.add_task(Task::new("b".to_owned(), String::new(), Priority::Low));
+        manager.store_to_file(file, None).unwrap();
+        fs::write(file, "garbage{").unwrap();
+
+        let args = |line: &[&str]| Args::parse(&line.iter().map(|arg| arg.to_string()).collect::<Vec<_>>()).unwrap();
+        assert!(Store::open_file(file.to_owned(), &args(&[])).is_err());
+        assert!(manage_backups(file, &args(&["list"])).is_ok());
+        assert!(manage_backups(file, &args(&["restore", "1"])).is_ok());
+
+        let store = Store::open_file(file.to_owned(), &args(&[])).unwrap();
+        assert_eq!(store.manager.tasks().len(), 1);
+        assert_eq!(store.manager.tasks()[0].name, "a");
+
+        let _ = fs::remove_dir_all(format!("{}.backups", file));
+        let _ = fs::remove_file(file);
+    }
+}
@@ -0,0 +1,419 @@
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+use crate::workflow::{RawWorkflow, Workflow};
+use crate::Priority;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+const DEFAULT_DATE_FORMAT: &str = "%d-%m-%Y %H:%M:%S";
+const DEFAULT_BACKUPS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Ru,
+    En,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Priority,
+    Date,
+    Name,
+    Due,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StorageMode {
+    Json,
+    Journal,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    None,
+    Git,
+}
+
+pub struct Colors {
+    pub high: &'static str,
+    pub medium: &'static str,
+    pub low: &'static str,
+    pub overdue: &'static str,
+}
+
+pub struct Config {
+    pub date_format: String,
+    pub default_priority: Priority,
+    pub data_file: Option<String>,
+    pub language: Language,
+    pub sort_order: SortOrder,
+    pub color: ColorMode,
+    pub colors: Colors,
+    pub hooks_dir: Option<PathBuf>,
+    pub attachments_dir: Option<PathBuf>,
+    pub user: Option<String>,
+    pub storage: StorageMode,
+    pub backups: usize,
+    pub sync: SyncMode,
+    pub workflow: Workflow,
+}
+
+#[derive(Deserialize, Default)]
+struct RawColors {
+    high: Option<String>,
+    medium: Option<String>,
+    low: Option<String>,
+    overdue: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    date_format: Option<String>,
+    default_priority: Option<String>,
+    data_file: Option<String>,
+    language: Option<String>,
+    sort_order: Option<String>,
+    color: Option<String>,
+    #[serde(default)]
+    colors: RawColors,
+    hooks_dir: Option<String>,
+    attachments_dir: Option<String>,
+    user: Option<String>,
+    storage: Option<String>,
+    #[serde(default, deserialize_with = "string_or_number")]
+    backups: Option<String>,
+    sync: Option<String>,
+    #[serde(default)]
+    workflow: RawWorkflow,
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match toml::Value::deserialize(deserializer)? {
+        toml::Value::Integer(number) => Some(number.to_string()),
+        toml::Value::String(text) => Some(text),
+        _ => return Err(serde::de::Error::custom("ожидается число")),
+    })
+}
+
+impl RawConfig {
+    fn from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("Ошибка при чтении файла настроек \"{}\": {}", path.display(), err))?;
+        toml::from_str(&content)
+            .map_err(|err| format!("Ошибка в файле настроек \"{}\": {}", path.display(), err))
+    }
+
+    fn set(&mut self, key: &str, value: String) -> bool {
+        let field = match key {
+            "date-format" => &mut self.date_format,
+            "default-priority" => &mut self.default_priority,
+            "file" => &mut self.data_file,
+            "lang" => &mut self.language,
+            "sort" => &mut self.sort_order,
+            "color" => &mut self.color,
+            "hooks-dir" => &mut self.hooks_dir,
+            "attachments-dir" => &mut self.attachments_dir,
+            "user" => &mut self.user,
+            "storage" => &mut self.storage,
+            "backups" => &mut self.backups,
+            "sync" => &mut self.sync,
+            _ => return false,
+        };
+        *field = Some(value);
+        true
+    }
+
+    fn apply_env(&mut self) {
+        for key in ["date-format", "default-priority", "file", "lang", "sort", "color", "hooks-dir", "attachments-dir", "user", "storage", "backups", "sync"] {
+            let name = format!("TASK_MANAGER_{}", key.to_uppercase().replace('-', "_"));
+            if let Ok(value) = std::env::var(name) {
+                self.set(key, value);
+            }
+        }
+    }
+}
+
+fn parse_color(name: Option<String>, default: &'static str) -> Result<&'static str, String> {
+    let name = match name {
+        Some(name) => name,
+        None => return Ok(default),
+    };
+
+    match name.to_lowercase().as_str() {
+        "none" => Ok(""),
+        "black" => Ok("30"),
+        "red" => Ok("31"),
+        "green" => Ok("32"),
+        "yellow" => Ok("33"),
+        "blue" => Ok("34"),
+        "magenta" => Ok("35"),
+        "cyan" => Ok("36"),
+        "white" => Ok("37"),
+        _ => Err(format!("Неизвестный цвет \"{}\"", name)),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| Path::new(&home).join(".task_manager"))
+}
+
+fn expand_home(path: String) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => Path::new(&home).join(rest).to_string_lossy().into_owned(),
+        _ => path,
+    }
+}
+
+impl Config {
+    fn from_raw(raw: RawConfig) -> Result<Self, String> {
+        let date_format = raw.date_format.unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_owned());
+        if StrftimeItems::new(&date_format).any(|item| item == Item::Error) {
+            return Err(format!("Неправильный формат даты \"{}\"", date_format));
+        }
+
+        let default_priority = match raw.default_priority {
+            Some(priority) => Priority::from_name(&priority)
+                .ok_or_else(|| format!("Неизвестный приоритет \"{}\"", priority))?,
+            None => Priority::Low,
+        };
+
+        let language = match raw.language.as_deref() {
+            None | Some("ru") => Language::Ru,
+            Some("en") => Language::En,
+            Some(language) => return Err(format!("Неизвестный язык \"{}\", доступны ru и en", language)),
+        };
+
+        let sort_order = match raw.sort_order.as_deref() {
+            None | Some("priority") => SortOrder::Priority,
+            Some("date") => SortOrder::Date,
+            Some("name") => SortOrder::Name,
+            Some("due") => SortOrder::Due,
+            Some(order) => return Err(format!("Неизвестный порядок сортировки \"{}\"", order)),
+        };
+
+        let color = match raw.color.as_deref() {
+            None | Some("auto") => ColorMode::Auto,
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            Some(color) => return Err(format!("Неизвестный режим цвета \"{}\"", color)),
+        };
+
+        let storage = match raw.storage.as_deref() {
+            None | Some("json") => StorageMode::Json,
+            Some("journal") => StorageMode::Journal,
+            Some(storage) => return Err(format!("Неизвестный формат хранения \"{}\", доступны json и journal", storage)),
+        };
+
+        let backups = match raw.backups {
+            Some(backups) => backups.parse().map_err(|_| format!("Неправильное количество резервных копий \"{}\"", backups))?,
+            None => DEFAULT_BACKUPS,
+        };
+
+        let sync = match raw.sync.as_deref() {
+            None | Some("none") => SyncMode::None,
+            Some("git") => SyncMode::Git,
+            Some(sync) => return Err(format!("Неизвестный режим синхронизации \"{}\", доступны none и git", sync)),
+        };
+
+        Ok(Self {
+            date_format,
+            default_priority,
+            data_file: raw.data_file.map(expand_home),
+            language,
+            sort_order,
+            color,
+            colors: Colors {
+                high: parse_color(raw.colors.high, "31")?,
+                medium: parse_color(raw.colors.medium, "33")?,
+                low: parse_color(raw.colors.low, "32")?,
+                overdue: parse_color(raw.colors.overdue, "31")?,
+            },
+            hooks_dir: raw.hooks_dir.map(|dir| PathBuf::from(expand_home(dir))).or_else(|| home_dir().map(|home| home.join("hooks"))),
+            attachments_dir: raw
+                .attachments_dir
+                .map(|dir| PathBuf::from(expand_home(dir)))
+                .or_else(|| home_dir().map(|home| home.join("attachments"))),
+            user: raw.user.or_else(|| std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok()).filter(|user| !user.is_empty()),
+            storage,
+            backups,
+            sync,
+            workflow: Workflow::from_raw(raw.workflow)?,
+        })
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        home_dir().map(|home| home.join("config.toml"))
+    }
+
+    pub fn load(args: &mut Vec<String>) -> Result<Self, String> {
+        let mut cli = vec![];
+        let mut config_path = std::env::var_os("TASK_MANAGER_CONFIG").map(PathBuf::from);
+        let mut index = 0;
+
+        while index < args.len() {
+            let key = match args[index].strip_prefix("--") {
+                Some(key) => key.to_owned(),
+                None => {
+                    index += 1;
+                    continue;
+                }
+            };
+
+            if key != "config" && !RawConfig::default().set(&key, String::new()) {
+                index += 1;
+                continue;
+            }
+
+            if index + 1 >= args.len() {
+                return Err(format!("Не указано значение параметра \"--{}\"", key));
+            }
+
+            let value = args.remove(index + 1);
+            args.remove(index);
+            if key == "config" {
+                config_path = Some(PathBuf::from(value));
+            } else {
+                cli.push((key, value));
+            }
+        }
+
+        let mut raw = match config_path {
+            Some(path) => RawConfig::from_file(&path)?,
+            None => match Self::default_path() {
+                Some(path) if path.exists() => RawConfig::from_file(&path)?,
+                _ => RawConfig::default(),
+            },
+        };
+
+        raw.apply_env();
+        for (key, value) in cli {
+            raw.set(&key, value);
+        }
+
+        Self::from_raw(raw)
+    }
+
+    pub fn use_color(&self) -> bool {
+        match self.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        }
+    }
+
+    pub fn format_day(&self, day: NaiveDate) -> String {
+        let mut items = vec![];
+        let mut after_time = false;
+
+        for item in StrftimeItems::new(&self.date_format) {
+            match item {
+                Item::Literal(_) | Item::OwnedLiteral(_) | Item::Space(_) | Item::OwnedSpace(_) => {
+                    if !after_time {
+                        items.push(item);
+                    }
+                }
+                Item::Numeric(
+                    Numeric::Year
+                    | Numeric::YearDiv100
+                    | Numeric::YearMod100
+                    | Numeric::IsoYear
+                    | Numeric::IsoYearDiv100
+                    | Numeric::IsoYearMod100
+                    | Numeric::Month
+                    | Numeric::Day
+                    | Numeric::WeekFromSun
+                    | Numeric::WeekFromMon
+                    | Numeric::IsoWeek
+                    | Numeric::NumDaysFromSun
+                    | Numeric::WeekdayFromMon
+                    | Numeric::Ordinal,
+                    _,
+                )
+                | Item::Fixed(Fixed::ShortMonthName | Fixed::LongMonthName | Fixed::ShortWeekdayName | Fixed::LongWeekdayName) => {
+                    after_time = false;
+                    items.push(item);
+                }
+                _ => after_time = true,
+            }
+        }
+
+        let text = day.format_with_items(items.into_iter()).to_string();
+        match text.trim() {
+            "" => day.to_string(),
+            text => text.to_owned(),
+        }
+    }
+}
+
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+pub fn current() -> &'static Config {
+    CONFIG.get_or_init(|| Config::from_raw(RawConfig::default()).unwrap_or_else(|msg| panic!("{}", msg)))
+}
+
+pub fn tr<'a>(ru: &'a str, en: &'a str) -> &'a str {
+    match current().language {
+        Language::Ru => ru,
+        Language::En => en,
+    }
+}
+
+macro_rules! trf {
+    ($ru:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::config::current().language {
+            $crate::config::Language::Ru => format!($ru $(, $arg)*),
+            $crate::config::Language::En => format!($en $(, $arg)*),
+        }
+    };
+}
+
+pub(crate) use trf;
+
+pub fn paint(text: &str, color: &str) -> String {
+    if color.is_empty() || !current().use_color() {
+        text.to_owned()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_date_format() {
+        let mut raw = RawConfig::default();
+        raw.set("date-format", "%Q".to_owned());
+        assert_eq!(Config::from_raw(raw).err().unwrap(), "Неправильный формат даты \"%Q\"");
+
+        let mut raw = RawConfig::default();
+        raw.set("date-format", "%Y-%m-%d %H:%M".to_owned());
+        assert!(Config::from_raw(raw).is_ok());
+    }
+
+    #[test]
+    fn formats_day_without_time() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        for (format, expected) in [("%d-%m-%Y %H:%M:%S", "19-10-2026"), ("%H:%M %d.%m.%Y", "19.10.2026"), ("%H:%M", "2026-10-19")] {
+            let mut raw = RawConfig::default();
+            raw.set("date-format", format.to_owned());
+            assert_eq!(Config::from_raw(raw).unwrap().format_day(day), expected);
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::trf;
+use crate::sync::{flatten, project_names, unflatten};
+use crate::{generate_id, Task, TasksManager};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stamp {
+    counter: u64,
+    replica: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    AddTask { stamp: Stamp, id: String },
+    RemoveTask { stamp: Stamp, id: String, observed: Vec<Stamp> },
+    SetField { stamp: Stamp, id: String, field: String, value: Value },
+    AddProject { stamp: Stamp, name: String },
+    SetSetting { stamp: Stamp, key: String, value: Value },
+}
+
+impl Change {
+    fn stamp(&self) -> &Stamp {
+        match self {
+            Change::AddTask { stamp, .. }
+            | Change::RemoveTask { stamp, .. }
+            | Change::SetField { stamp, .. }
+            | Change::AddProject { stamp, .. }
+            | Change::SetSetting { stamp, .. } => stamp,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChangeSet {
+    pub replica: String,
+    pub clock: u64,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Default)]
+struct State {
+    adds: HashMap<String, BTreeSet<Stamp>>,
+    removed: HashSet<Stamp>,
+    fields: HashMap<String, BTreeMap<String, (Stamp, Value)>>,
+    projects: BTreeMap<String, Stamp>,
+    settings: BTreeMap<String, (Stamp, Value)>,
+}
+
+const LIST_FIELDS: [&str; 3] = ["notes", "history", "time_entries"];
+
+fn same_entry(existing: &Value, entry: &Value) -> bool {
+    match (existing.get("start"), entry.get("start")) {
+        (Some(start), Some(other)) => start == other,
+        _ => existing == entry,
+    }
+}
+
+fn merge_list(current: &Value, incoming: &Value) -> Value {
+    let mut entries = current.as_array().cloned().unwrap_or_default();
+    for entry in incoming.as_array().into_iter().flatten() {
+        match entries.iter_mut().find(|existing| same_entry(existing, entry)) {
+            Some(existing) => {
+                if !entry["end"].is_null() && (existing["end"].is_null() || entry["end"].as_str() > existing["end"].as_str()) {
+                    *existing = entry.clone();
+                }
+            }
+            None => entries.push(entry.clone()),
+        }
+    }
+
+    let key = |entry: &Value| (entry.get("time").or(entry.get("start")).and_then(Value::as_str).unwrap_or_default().to_owned(), entry.to_string());
+    entries.sort_by_key(key);
+    Value::Array(entries)
+}
+
+fn set_register(register: Option<&mut (Stamp, Value)>, stamp: &Stamp, value: &Value) -> Option<(Stamp, Value)> {
+    match register {
+        Some(register) if register.0 >= *stamp => None,
+        Some(register) => {
+            *register = (stamp.clone(), value.clone());
+            None
+        }
+        None => Some((stamp.clone(), value.clone())),
+    }
+}
+
+impl State {
+    fn apply(&mut self, change: &Change) {
+        match change {
+            Change::AddTask { stamp, id } => {
+                self.adds.entry(id.clone()).or_default().insert(stamp.clone());
+            }
+            Change::RemoveTask { observed, .. } => self.removed.extend(observed.iter().cloned()),
+            Change::SetField { stamp, id, field, value } if LIST_FIELDS.contains(&field.as_str()) => {
+                let fields = self.fields.entry(id.clone()).or_default();
+                let register = fields.entry(field.clone()).or_insert_with(|| (stamp.clone(), Value::Array(vec![])));
+                register.1 = merge_list(&register.1, value);
+                if *stamp > register.0 {
+                    register.0 = stamp.clone();
+                }
+            }
+            Change::SetField { stamp, id, field, value } => {
+                let fields = self.fields.entry(id.clone()).or_default();
+                if let Some(register) = set_register(fields.get_mut(field), stamp, value) {
+                    fields.insert(field.clone(), register);
+                }
+            }
+            Change::AddProject { stamp, name } => {
+                let first = self.projects.entry(name.clone()).or_insert_with(|| stamp.clone());
+                if *stamp < *first {
+                    *first = stamp.clone();
+                }
+            }
+            Change::SetSetting { stamp, key, value } => {
+                if let Some(register) = set_register(self.settings.get_mut(key), stamp, value) {
+                    self.settings.insert(key.clone(), register);
+                }
+            }
+        }
+    }
+
+    fn live_adds(&self, id: &str) -> Vec<Stamp> {
+        self.adds
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter(|stamp| !self.removed.contains(*stamp))
+            .cloned()
+            .collect()
+    }
+
+    fn live_tasks(&self) -> Vec<(Stamp, &String)> {
+        let mut tasks: Vec<(Stamp, &String)> = self
+            .adds
+            .keys()
+            .filter_map(|id| self.live_adds(id).into_iter().next().map(|stamp| (stamp, id)))
+            .collect();
+        tasks.sort();
+        tasks
+    }
+
+    fn task(&self, id: &str) -> Value {
+        let fields = self.fields.get(id).into_iter().flatten();
+        Value::Object(fields.map(|(field, (_, value))| (field.clone(), value.clone())).collect())
+    }
+
+    fn setting(&self, key: &str) -> Value {
+        self.settings.get(key).map(|(_, value)| value.clone()).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Replica {
+    id: String,
+    clock: u64,
+    changes: Vec<Change>,
+    #[serde(skip)]
+    state: State,
+    #[serde(skip)]
+    known: HashSet<Stamp>,
+}
+
+fn replica_path(filename: &str) -> String {
+    format!("{}.replica", filename)
+}
+
+impl Replica {
+    pub fn new() -> Self {
+        Self { id: generate_id(), clock: 0, changes: vec![], state: State::default(), known: HashSet::new() }
+    }
+
+    pub fn load(filename: &str) -> Result<Self, String> {
+        let path = replica_path(filename);
+        if !Path::new(&path).exists() {
+            return Ok(Self::new());
+        }
+
+        let data = fs::read(&path).map_err(|err| trf!("Ошибка при чтении файла \"{}\": {}", "Error reading file \"{}\": {}", path, err))?;
+        let mut replica: Self = serde_json::from_slice(&data)
+            .map_err(|err| trf!("Ошибка при чтении файла \"{}\": {}", "Error reading file \"{}\": {}", path, err))?;
+        for change in &replica.changes {
+            replica.state.apply(change);
+            replica.known.insert(change.stamp().clone());
+        }
+        Ok(replica)
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), String> {
+        let path = replica_path(filename);
+        let data = serde_json::to_vec(self).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+        fs::write(&path, data).map_err(|err| trf!("Ошибка при записи в файл \"{}\": {}", "Error writing file \"{}\": {}", path, err))
+    }
+
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    fn push(&mut self, change: Change) {
+        self.state.apply(&change);
+        self.known.insert(change.stamp().clone());
+        self.changes.push(change);
+    }
+
+    fn tick(&mut self) -> Stamp {
+        self.clock += 1;
+        Stamp { counter: self.clock, replica: self.id.clone() }
+    }
+
+    pub fn record(&mut self, manager: &TasksManager) -> Result<usize, String> {
+        let data = serde_json::to_value(manager).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+        let count = self.changes.len();
+
+        for name in project_names(&data) {
+            if !self.state.projects.contains_key(&name) {
+                let stamp = self.tick();
+                self.push(Change::AddProject { stamp, name });
+            }
+        }
+
+        let tasks = flatten(&data);
+        for (id, task) in &tasks {
+            if self.state.live_adds(id).is_empty() {
+                let stamp = self.tick();
+                self.push(Change::AddTask { stamp, id: id.clone() });
+            }
+
+            for (field, value) in task.as_object().into_iter().flatten() {
+                let current = self.state.fields.get(id).and_then(|fields| fields.get(field));
+                if current.map(|(_, current)| current) != Some(value) {
+                    let stamp = self.tick();
+                    self.push(Change::SetField { stamp, id: id.clone(), field: field.clone(), value: value.clone() });
+                }
+            }
+        }
+
+        let removed: Vec<String> = self
+            .state
+            .live_tasks()
+            .into_iter()
+            .map(|(_, id)| id.clone())
+            .filter(|id| !tasks.iter().any(|(task_id, _)| task_id == id))
+            .collect();
+        for id in removed {
+            let stamp = self.tick();
+            let observed = self.state.live_adds(&id);
+            self.push(Change::RemoveTask { stamp, id, observed });
+        }
+
+        if self.state.setting("auto_archive_days") != data["auto_archive_days"] {
+            let stamp = self.tick();
+            let value = data["auto_archive_days"].clone();
+            self.push(Change::SetSetting { stamp, key: "auto_archive_days".to_owned(), value });
+        }
+
+        Ok(self.changes.len() - count)
+    }
+
+    pub fn export(&self, since: u64) -> ChangeSet {
+        ChangeSet {
+            replica: self.id.clone(),
+            clock: self.clock,
+            changes: self.changes.iter().filter(|change| change.stamp().counter > since).cloned().collect(),
+        }
+    }
+
+    pub fn import(&mut self, set: ChangeSet) -> usize {
+        let mut count = 0;
+        for change in set.changes {
+            if self.known.contains(change.stamp()) {
+                continue;
+            }
+            self.clock = self.clock.max(change.stamp().counter);
+            self.push(change);
+            count += 1;
+        }
+        self.clock = self.clock.max(set.clock);
+        count
+    }
+
+    pub fn to_manager(&self) -> Result<TasksManager, String> {
+        let mut projects: Vec<(&Stamp, &String)> = self.state.projects.iter().map(|(name, stamp)| (stamp, name)).collect();
+        projects.sort();
+        let projects: Vec<String> = projects.into_iter().map(|(_, name)| name.clone()).collect();
+
+        let mut tasks = vec![];
+        for (_, id) in self.state.live_tasks() {
+            let task = self.state.task(id);
+            if let Err(err) = serde_json::from_value::<Task>(task.clone()) {
+                return Err(trf!("Задача {} в журнале реплики повреждена: {}", "Task {} in the replica log is corrupted: {}", id, err));
+            }
+            tasks.push(task);
+        }
+
+        TasksManager::from_json(unflatten(&projects, tasks, self.state.setting("auto_archive_days")))
+            .map_err(|err| trf!("Ошибка при применении изменений: {}", "Error applying changes: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Priority;
+
+    fn exchange(from: &Replica, to: &mut Replica) -> TasksManager {
+        let set: ChangeSet = serde_json::from_str(&serde_json::to_string(&from.export(0)).unwrap()).unwrap();
+        to.import(set);
+        to.to_manager().unwrap()
+    }
+
+    #[test]
+    fn diverged_replicas_converge_in_any_order() {
+        let mut manager = TasksManager::new();
+        for name in ["a", "b"] {
+            manager.add_task(Task::new(name.to_owned(), String::new(), Priority::Low));
+        }
+
+        let mut first = Replica::new();
+        first.record(&manager).unwrap();
+        let mut second = Replica::new();
+        let mut copy = exchange(&first, &mut second);
+
+        manager.tasks_mut()[0].description = "первая".to_owned();
+        manager.tasks_mut()[1].priority = Priority::High;
+        manager.create_project("Дом").unwrap();
+        first.record(&manager).unwrap();
+
+        copy.tasks_mut()[0].description = "вторая".to_owned();
+        copy.tasks_mut().remove(1);
+        second.record(&copy).unwrap();
+
+        let merged_first = exchange(&second, &mut first);
+        let merged_second = exchange(&first, &mut second);
+
+        assert_eq!(serde_json::to_value(&merged_first).unwrap(), serde_json::to_value(&merged_second).unwrap());
+        assert_eq!(merged_first.tasks().len(), 1);
+        assert_eq!(merged_first.projects.len(), 2);
+        assert_eq!(merged_first.tasks()[0].description, "первая");
+    }
+
+    #[test]
+    fn import_is_idempotent_and_since_filters_changes() {
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        let mut first = Replica::new();
+        first.record(&manager).unwrap();
+        let clock = first.clock();
+
+        manager.tasks_mut()[0].name = "b".to_owned();
+        assert_eq!(first.record(&manager).unwrap(), 1);
+        assert_eq!(first.record(&manager).unwrap(), 0);
+        assert_eq!(first.export(clock).changes.len(), 1);
+
+        let mut second = Replica::new();
+        let count = second.import(first.export(0));
+        assert_eq!(second.import(first.export(0)), 0);
+        assert_eq!(count, first.changes.len());
+        assert_eq!(second.to_manager().unwrap().tasks()[0].name, "b");
+        assert!(second.clock() >= first.clock());
+    }
+
+    #[test]
+    fn concurrent_notes_and_timers_are_kept_from_both_sides() {
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        let mut first = Replica::new();
+        first.record(&manager).unwrap();
+        let mut second = Replica::new();
+        let mut copy = exchange(&first, &mut second);
+
+        manager.tasks_mut()[0].add_note("первая").unwrap();
+        manager.start_timer("a").unwrap();
+        first.record(&manager).unwrap();
+        copy.tasks_mut()[0].add_note("вторая").unwrap();
+        second.record(&copy).unwrap();
+
+        let merged = exchange(&second, &mut first);
+        let notes: Vec<&str> = merged.tasks()[0].notes.iter().map(|note| note.text.as_str()).collect();
+        assert_eq!(notes, ["первая", "вторая"]);
+        assert_eq!(merged.tasks()[0].history.len(), 4);
+
+        manager.stop_timer().unwrap();
+        first.record(&manager).unwrap();
+        let merged = exchange(&first, &mut second);
+        assert_eq!(merged.tasks()[0].time_entries.len(), 1);
+        assert!(!merged.tasks()[0].is_timer_running());
+    }
+
+    #[test]
+    fn reports_tasks_that_cannot_be_restored() {
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        let id = manager.tasks()[0].id.clone();
+        let mut replica = Replica::new();
+        replica.record(&manager).unwrap();
+
+        let stamp = replica.tick();
+        replica.push(Change::SetField { stamp, id: id.clone(), field: "priority".to_owned(), value: Value::from("???") });
+        assert!(replica.to_manager().err().unwrap().starts_with(&format!("Задача {} в журнале реплики повреждена", id)));
+    }
+}
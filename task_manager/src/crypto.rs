@@ -0,0 +1,84 @@
+use std::fs::File;
+use std::io::Read;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use crate::config::{tr, trf};
+
+const MAGIC: &[u8] = b"TMENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const CHECK_LEN: usize = 32;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + CHECK_LEN + NONCE_LEN;
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn is_encrypted_file(filename: &str) -> bool {
+    let mut magic = [0u8; MAGIC.len()];
+    match File::open(filename) {
+        Ok(mut file) => file.read_exact(&mut magic).is_ok() && is_encrypted(&magic),
+        Err(_) => false,
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<([u8; 32], [u8; CHECK_LEN]), String> {
+    let mut output = [0u8; 32 + CHECK_LEN];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut output)
+        .map_err(|err| trf!("Ошибка при получении ключа из пароля: {}", "Error deriving key from password: {}", err))?;
+
+    let mut key = [0u8; 32];
+    let mut check = [0u8; CHECK_LEN];
+    key.copy_from_slice(&output[..32]);
+    check.copy_from_slice(&output[32..]);
+    Ok((key, check))
+}
+
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let (key, check) = derive_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&check);
+    header.extend_from_slice(&nonce);
+
+    let payload = chacha20poly1305::aead::Payload { msg: data, aad: &header };
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), payload)
+        .map_err(|_| tr("Ошибка при шифровании данных", "Error encrypting data").to_owned())?;
+
+    header.extend_from_slice(&ciphertext);
+    Ok(header)
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if !is_encrypted(data) || data.len() < HEADER_LEN {
+        return Err(tr("Файл не является зашифрованным хранилищем задач или повреждён", "File is not an encrypted task store or is corrupted").to_owned());
+    }
+
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let salt = &header[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let stored_check = &header[MAGIC.len() + SALT_LEN..MAGIC.len() + SALT_LEN + CHECK_LEN];
+    let nonce = &header[MAGIC.len() + SALT_LEN + CHECK_LEN..];
+
+    let (key, check) = derive_key(passphrase, salt)?;
+    if check.as_slice() != stored_check {
+        return Err(tr("Неверный пароль", "Wrong password").to_owned());
+    }
+
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let payload = chacha20poly1305::aead::Payload { msg: ciphertext, aad: header };
+    cipher
+        .decrypt(XNonce::from_slice(nonce), payload)
+        .map_err(|_| tr("Файл повреждён или был изменён: проверка целостности не пройдена", "File is corrupted or was modified: integrity check failed").to_owned())
+}
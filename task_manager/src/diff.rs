@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::io::Write;
+use serde::Serialize;
+use serde_json::Value;
+use crate::config::{tr, trf};
+use crate::sync::flatten;
+use crate::TasksManager;
+
+const IGNORED_FIELDS: [&str; 2] = ["id", "history"];
+
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum TaskChange {
+    Added { id: String, name: String, project: String },
+    Removed { id: String, name: String, project: String },
+    Modified { id: String, name: String, project: String, fields: Vec<FieldChange> },
+}
+
+fn text(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_owned()
+}
+
+fn field_changes(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let mut fields: Vec<&String> = new.as_object().into_iter().flatten().map(|(field, _)| field).collect();
+    for field in old.as_object().into_iter().flatten().map(|(field, _)| field) {
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+
+    fields
+        .into_iter()
+        .filter(|field| !IGNORED_FIELDS.contains(&field.as_str()) && old[field.as_str()] != new[field.as_str()])
+        .map(|field| FieldChange { field: field.clone(), old: old[field.as_str()].clone(), new: new[field.as_str()].clone() })
+        .collect()
+}
+
+pub fn diff(old: &TasksManager, new: &TasksManager) -> Result<Vec<TaskChange>, String> {
+    let to_value = |manager| serde_json::to_value(manager).map_err(|err| trf!("Ошибка при сравнении задач: {}", "Error comparing tasks: {}", err));
+    let old_tasks = flatten(&to_value(old)?);
+    let new_tasks = flatten(&to_value(new)?);
+    let old_by_id: HashMap<&String, &Value> = old_tasks.iter().map(|(id, task)| (id, task)).collect();
+    let mut changes = vec![];
+
+    for (id, task) in &new_tasks {
+        let (name, project) = (text(&task["name"]), text(&task["project"]));
+        match old_by_id.get(id) {
+            None => changes.push(TaskChange::Added { id: id.clone(), name, project }),
+            Some(old) => {
+                let fields = field_changes(old, task);
+                if !fields.is_empty() {
+                    changes.push(TaskChange::Modified { id: id.clone(), name: text(&old["name"]), project, fields });
+                }
+            }
+        }
+    }
+
+    for (id, task) in &old_tasks {
+        if !new_tasks.iter().any(|(new_id, _)| new_id == id) {
+            changes.push(TaskChange::Removed { id: id.clone(), name: text(&task["name"]), project: text(&task["project"]) });
+        }
+    }
+
+    Ok(changes)
+}
+
+pub fn print_diff(out: &mut impl Write, changes: &[TaskChange]) -> std::io::Result<()> {
+    if changes.is_empty() {
+        writeln!(out, "{}", tr("Различий нет", "No differences"))?;
+        return Ok(());
+    }
+
+    for change in changes {
+        match change {
+            TaskChange::Added { name, project, .. } => writeln!(out, "{}", trf!("+ Задача \"{}\" (проект \"{}\")", "+ Task \"{}\" (project \"{}\")", name, project))?,
+            TaskChange::Removed { name, project, .. } => writeln!(out, "{}", trf!("- Задача \"{}\" (проект \"{}\")", "- Task \"{}\" (project \"{}\")", name, project))?,
+            TaskChange::Modified { name, project, fields, .. } => {
+                writeln!(out, "{}", trf!("~ Задача \"{}\" (проект \"{}\"):", "~ Task \"{}\" (project \"{}\"):", name, project))?;
+                for field in fields {
+                    writeln!(out, "    {}: {} -> {}", field.field, field.old, field.new)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Priority, Task};
+
+    #[test]
+    fn reports_added_removed_and_modified_fields() {
+        let mut old = TasksManager::new();
+        for name in ["a", "b"] {
+            old.add_task(Task::new(name.to_owned(), String::new(), Priority::Low));
+        }
+
+        let mut new = TasksManager::from_json(serde_json::to_value(&old).unwrap()).unwrap();
+        new.tasks_mut()[0].priority = Priority::High;
+        new.remove_task("b").unwrap();
+        new.add_task(Task::new("c".to_owned(), String::new(), Priority::Low));
+
+        let changes = diff(&old, &new).unwrap();
+        let mut out = vec![];
+        print_diff(&mut out, &changes).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "~ Задача \"a\" (проект \"Основной\"):\n    priority: \"Low\" -> \"High\"\n\
+             + Задача \"c\" (проект \"Основной\")\n\
+             - Задача \"b\" (проект \"Основной\")\n"
+        );
+        assert!(diff(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_fields_missing_on_either_side() {
+        let old = serde_json::json!({ "id": "1", "name": "a", "due_date": "2026-10-19" });
+        let new = serde_json::json!({ "id": "2", "name": "a", "assignee": "ivan" });
+
+        let fields: Vec<(String, Value, Value)> = field_changes(&old, &new)
+            .into_iter()
+            .map(|change| (change.field, change.old, change.new))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("assignee".to_owned(), Value::Null, Value::from("ivan")),
+                ("due_date".to_owned(), Value::from("2026-10-19"), Value::Null),
+            ]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::config::{self, tr, trf};
+use crate::{users, Priority, Task};
+
+enum Term {
+    Text(String),
+    Note(String),
+    Tag(String),
+    Priority(Priority),
+    Completed,
+    Open,
+    Overdue,
+    Status(String),
+    Assignee(Option<String>),
+}
+
+pub struct Filter {
+    terms: Vec<Term>,
+}
+
+impl Filter {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut terms = vec![];
+
+        for word in query.split_whitespace() {
+            let term = if let Some(tag) = word.strip_prefix('#').or_else(|| word.strip_prefix("tag:")) {
+                Term::Tag(tag.to_lowercase())
+            } else if let Some(priority) = word.strip_prefix("priority:") {
+                Term::Priority(
+                    Priority::from_name(priority).ok_or_else(|| trf!("Неизвестный приоритет \"{}\"", "Unknown priority \"{}\"", priority))?,
+                )
+            } else if let Some(assignee) = word.strip_prefix('@').or_else(|| word.strip_prefix("assignee:")) {
+                match assignee {
+                    "" => return Err(trf!("Не указан исполнитель в \"{}\"", "Assignee is not specified in \"{}\"", word)),
+                    "none" => Term::Assignee(None),
+                    "me" => match users::current_user() {
+                        Some(user) => Term::Assignee(Some(user.to_owned())),
+                        None => return Err(tr("Текущий пользователь не задан, укажите параметр user", "Current user is not set, specify the user setting").to_owned()),
+                    },
+                    assignee => Term::Assignee(Some(assignee.to_owned())),
+                }
+            } else if let Some(text) = word.strip_prefix("note:") {
+                Term::Note(text.to_lowercase())
+            } else if let Some(status) = word.strip_prefix("status:") {
+                match (status, config::current().workflow.find_state(status)) {
+                    (_, Some(state)) => Term::Status(state.to_owned()),
+                    ("done" | "выполнено", None) => Term::Completed,
+                    ("open" | "невыполнено", None) => Term::Open,
+                    ("overdue" | "просрочено", None) => Term::Overdue,
+                    _ => {
+                        return Err(trf!(
+                            "Неизвестный статус \"{}\", доступны done, open, overdue и статусы workflow",
+                            "Unknown status \"{}\", available: done, open, overdue and workflow statuses",
+                            status
+                        ))
+                    }
+                }
+            } else {
+                Term::Text(word.to_lowercase())
+            };
+            terms.push(term);
+        }
+
+        Ok(Self { terms })
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Text(text) => {
+                task.name.to_lowercase().contains(text)
+                    || task.description.to_lowercase().contains(text)
+                    || task.notes_contain(text)
+            }
+            Term::Note(text) => task.notes_contain(text),
+            Term::Tag(tag) => task.tags.iter().any(|task_tag| task_tag.to_lowercase() == *tag),
+            Term::Priority(priority) => task.priority == *priority,
+            Term::Completed => task.is_completed(),
+            Term::Open => !task.is_closed(),
+            Term::Overdue => task.is_overdue(),
+            Term::Status(status) => task.status == *status,
+            Term::Assignee(Some(assignee)) => task.is_assigned_to(assignee),
+            Term::Assignee(None) => task.assignee.is_none(),
+        })
+    }
+}
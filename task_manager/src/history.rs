@@ -0,0 +1,86 @@
+use std::fmt;
+use std::io::Write;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use crate::config::{self, tr, trf};
+use crate::Task;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum HistoryEvent {
+    Created,
+    Edited { field: String, old: String, new: String },
+    Completed,
+    Reopened,
+    Archived,
+    Restored,
+    StatusChanged { old: String, new: String },
+    Assigned { old: Option<String>, new: Option<String> },
+    NoteAdded,
+    TimerStarted,
+    TimerStopped,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub time: DateTime<Local>,
+    pub event: HistoryEvent,
+}
+
+impl fmt::Display for HistoryEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryEvent::Created => write!(f, "{}", tr("Задача создана", "Task created")),
+            HistoryEvent::Edited { field, old, new } => {
+                write!(f, "{}", trf!("Изменено поле \"{}\": \"{}\" -> \"{}\"", "Field \"{}\" changed: \"{}\" -> \"{}\"", field, old, new))
+            }
+            HistoryEvent::Completed => write!(f, "{}", tr("Задача выполнена", "Task completed")),
+            HistoryEvent::Reopened => write!(f, "{}", tr("Задача открыта повторно", "Task reopened")),
+            HistoryEvent::Archived => write!(f, "{}", tr("Задача перемещена в архив", "Task moved to archive")),
+            HistoryEvent::Restored => write!(f, "{}", tr("Задача восстановлена из архива", "Task restored from archive")),
+            HistoryEvent::StatusChanged { old, new } => write!(f, "{}", trf!("Статус изменён: \"{}\" -> \"{}\"", "Status changed: \"{}\" -> \"{}\"", old, new)),
+            HistoryEvent::Assigned { old, new } => write!(
+                f,
+                "{}",
+                trf!(
+                    "Исполнитель изменён: {} -> {}",
+                    "Assignee changed: {} -> {}",
+                    old.as_deref().unwrap_or(tr("не назначен", "unassigned")),
+                    new.as_deref().unwrap_or(tr("не назначен", "unassigned"))
+                )
+            ),
+            HistoryEvent::NoteAdded => write!(f, "{}", tr("Добавлена заметка", "Note added")),
+            HistoryEvent::TimerStarted => write!(f, "{}", tr("Таймер запущен", "Timer started")),
+            HistoryEvent::TimerStopped => write!(f, "{}", tr("Таймер остановлен", "Timer stopped")),
+        }
+    }
+}
+
+impl Task {
+    pub fn record(&mut self, event: HistoryEvent) {
+        self.history.push(HistoryEntry { time: Local::now(), event });
+    }
+
+    pub fn record_edit(&mut self, field: &str, old: String, new: String) {
+        if old != new {
+            self.record(HistoryEvent::Edited { field: field.to_owned(), old, new });
+        }
+    }
+
+    pub fn last_modified(&self) -> DateTime<Local> {
+        self.history.last().map_or(self.add_time, |entry| entry.time)
+    }
+
+    pub fn print_history(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.history.is_empty() {
+            writeln!(out, "{}", trf!("История задачи \"{}\" пуста", "History of task \"{}\" is empty", self.name))?;
+            return Ok(());
+        }
+
+        writeln!(out, "{}", trf!("История задачи \"{}\":", "History of task \"{}\":", self.name))?;
+        for entry in &self.history {
+            writeln!(out, "{}: {}", entry.time.format(&config::current().date_format), entry.event)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use crate::config::trf;
+use crate::attachments::Attachment;
+use crate::{Task, TasksManager};
+
+#[derive(Clone, Copy)]
+pub enum HookEvent {
+    Add,
+    Edit,
+    Complete,
+    Remove,
+}
+
+impl HookEvent {
+    fn prefix(&self) -> &'static str {
+        match self {
+            HookEvent::Add => "on-add",
+            HookEvent::Edit => "on-edit",
+            HookEvent::Complete => "on-complete",
+            HookEvent::Remove => "on-remove",
+        }
+    }
+}
+
+pub struct Hooks {
+    dir: Option<PathBuf>,
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+impl Hooks {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    fn scripts(&self, event: HookEvent) -> Vec<PathBuf> {
+        let entries = match self.dir.as_ref().map(fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            _ => return vec![],
+        };
+
+        let mut scripts: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(event.prefix()))
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '_', '.']))
+            })
+            .filter(|path| is_executable(path))
+            .collect();
+        scripts.sort();
+        scripts
+    }
+
+    fn run_script(script: &Path, event: HookEvent, input: &str) -> Result<(Option<Task>, Vec<String>), String> {
+        let script_name = script.display();
+        let mut child = Command::new(script)
+            .env("TASK_MANAGER_HOOK_EVENT", event.prefix())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| trf!("Ошибка при запуске хука \"{}\": {}", "Error starting hook \"{}\": {}", script_name, err))?;
+
+        let stdin = child.stdin.take();
+        let input = input.to_owned();
+        let writer = thread::spawn(move || match stdin.map(|mut stdin| stdin.write_all(input.as_bytes())) {
+            Some(Err(err)) if err.kind() != ErrorKind::BrokenPipe => Err(err),
+            _ => Ok(()),
+        });
+
+        let output = child.wait_with_output()
+            .map_err(|err| trf!("Ошибка при выполнении хука \"{}\": {}", "Error running hook \"{}\": {}", script_name, err))?;
+        writer.join().unwrap_or(Ok(())).map_err(|err| trf!("Ошибка при передаче задачи хуку \"{}\": {}", "Error passing task to hook \"{}\": {}", script_name, err))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+
+        let mut task = None;
+        let mut feedback = vec![];
+        if let Some(first) = lines.next() {
+            match serde_json::from_str::<Task>(first) {
+                Ok(modified) => task = Some(modified),
+                Err(_) if first.trim_start().starts_with('{') => {
+                    return Err(trf!("Хук \"{}\" вернул некорректную задачу", "Hook \"{}\" returned an invalid task", script_name));
+                }
+                Err(_) => feedback.push(first.to_owned()),
+            }
+        }
+        feedback.extend(lines.map(str::to_owned));
+        feedback.extend(String::from_utf8_lossy(&output.stderr).lines().map(str::to_owned));
+
+        if output.status.success() {
+            Ok((task, feedback))
+        } else {
+            let mut message = trf!("Хук \"{}\" отклонил изменение", "Hook \"{}\" rejected the change", script_name);
+            for line in feedback {
+                message.push_str(&format!("\n{}", line));
+            }
+            Err(message)
+        }
+    }
+
+    pub fn run(&self, event: HookEvent, original: Option<&Task>, mut task: Task) -> Result<(Task, Vec<String>), String> {
+        let original = match original {
+            Some(original) => Some(serde_json::to_string(original).map_err(|err| err.to_string())?),
+            None => None,
+        };
+        let mut feedback = vec![];
+
+        for script in self.scripts(event) {
+            let mut input = String::new();
+            if let Some(original) = &original {
+                input.push_str(original);
+                input.push('\n');
+            }
+            input.push_str(&serde_json::to_string(&task).map_err(|err| err.to_string())?);
+            input.push('\n');
+
+            let (modified, messages) = Self::run_script(&script, event, &input)?;
+            if let Some(mut modified) = modified {
+                modified.id = task.id.clone();
+                task = modified;
+            }
+            feedback.extend(messages);
+        }
+
+        Ok((task, feedback))
+    }
+}
+
+fn with_feedback(message: String, feedback: Vec<String>) -> String {
+    feedback.into_iter().fold(message, |message, line| format!("{}\n{}", message, line))
+}
+
+impl TasksManager {
+    fn check_hook_rename(&self, index: Option<usize>, name: &str, task: &Task) -> Result<(), String> {
+        let taken = self.tasks().iter().enumerate().any(|(other, existing)| Some(other) != index && existing.name == task.name);
+        if task.name != name && taken {
+            return Err(trf!(
+                "Хук переименовал задачу \"{}\" в \"{}\", но задача с таким именем уже существует",
+                "A hook renamed task \"{}\" to \"{}\", but a task with that name already exists",
+                name,
+                task.name
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn add_task_with_hooks(&mut self, hooks: &Hooks, task: Task) -> Result<String, String> {
+        if self.find_task(&task.name).is_some() {
+            return Err(trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", task.name));
+        }
+
+        let name = task.name.clone();
+        let (task, feedback) = hooks.run(HookEvent::Add, None, task)?;
+        self.check_hook_rename(None, &name, &task)?;
+        let message = trf!("Задача \"{}\" добавлена", "Task \"{}\" added", task.name);
+        self.add_task(task);
+        Ok(with_feedback(message, feedback))
+    }
+
+    pub fn edit_task_with_hooks(&mut self, hooks: &Hooks, name: &str, updated_task: Task) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let original = self.tasks()[index].clone();
+        let message = self.edit_task(name, updated_task)?;
+        self.run_edit_hooks(hooks, index, original, message)
+    }
+
+    pub fn edit_at_with_hooks(&mut self, hooks: &Hooks, index: usize, updated_task: Task) -> Result<String, String> {
+        if updated_task.name != self.tasks()[index].name && self.find_task(&updated_task.name).is_some() {
+            return Err(trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", updated_task.name));
+        }
+
+        let original = self.tasks()[index].clone();
+        self.tasks_mut()[index].apply_edit(updated_task);
+        let message = trf!("Задача \"{}\" обновлена успешно", "Task \"{}\" updated", original.name);
+        self.run_edit_hooks(hooks, index, original, message)
+    }
+
+    fn run_edit_hooks(&mut self, hooks: &Hooks, index: usize, original: Task, message: String) -> Result<String, String> {
+        let result = hooks.run(HookEvent::Edit, Some(&original), self.tasks()[index].clone()).and_then(|(modified, feedback)| {
+            self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+            Ok((modified, feedback))
+        });
+
+        match result {
+            Ok((modified, feedback)) => {
+                self.tasks_mut()[index] = modified;
+                Ok(with_feedback(message, feedback))
+            }
+            Err(msg) => {
+                self.tasks_mut()[index] = original;
+                Err(msg)
+            }
+        }
+    }
+
+    pub fn complete_task_with_hooks(&mut self, hooks: &Hooks, name: &str) -> Result<String, String> {
+        match self.find_task(name) {
+            Some(index) => self.complete_at_with_hooks(hooks, index),
+            None => Err(trf!("Задачи с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        }
+    }
+
+    pub fn complete_at_with_hooks(&mut self, hooks: &Hooks, index: usize) -> Result<String, String> {
+        let mut modified = self.tasks()[index].clone();
+        modified.mark_as_completed()?;
+
+        let (modified, feedback) = hooks.run(HookEvent::Complete, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        let message = trf!("Задача \"{}\" отмечена как выполненная", "Task \"{}\" marked as completed", self.tasks()[index].name);
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(message, feedback))
+    }
+
+    pub fn set_status_with_hooks(&mut self, hooks: &Hooks, name: &str, status: &str) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let mut modified = self.tasks()[index].clone();
+        modified.set_status(status)?;
+
+        let (modified, feedback) = hooks.run(HookEvent::Edit, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(trf!("Задача \"{}\" переведена в статус \"{}\"", "Task \"{}\" moved to status \"{}\"", name, status), feedback))
+    }
+
+    pub fn assign_task_with_hooks(&mut self, hooks: &Hooks, name: &str, assignee: Option<String>) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let mut modified = self.tasks()[index].clone();
+        modified.assign(assignee)?;
+
+        let (modified, feedback) = hooks.run(HookEvent::Edit, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        let message = match &modified.assignee {
+            Some(assignee) => trf!("Задача \"{}\" назначена на {}", "Task \"{}\" assigned to {}", name, assignee),
+            None => trf!("С задачи \"{}\" снят исполнитель", "Task \"{}\" unassigned", name),
+        };
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(message, feedback))
+    }
+
+    pub fn tag_at_with_hooks(&mut self, hooks: &Hooks, index: usize, tag: &str) -> Result<String, String> {
+        let name = self.tasks()[index].name.clone();
+        let mut modified = self.tasks()[index].clone();
+        if !modified.add_tag(tag) {
+            return Ok(trf!("У задачи \"{}\" уже есть тег #{}", "Task \"{}\" already has tag #{}", name, tag));
+        }
+
+        let (modified, feedback) = hooks.run(HookEvent::Edit, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(trf!("Задаче \"{}\" добавлен тег #{}", "Tag #{1} added to task \"{0}\"", name, tag), feedback))
+    }
+
+    pub fn attach_with_hooks(&mut self, hooks: &Hooks, name: &str, input: &str, copy: bool) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let mut modified = self.tasks()[index].clone();
+        let attachment = Attachment::parse(input, &modified.id, copy)?;
+        if !modified.attach(attachment.clone()) {
+            return Ok(trf!("У задачи \"{}\" уже есть вложение \"{}\"", "Task \"{}\" already has attachment \"{}\"", name, input));
+        }
+
+        let (modified, feedback) = hooks.run(HookEvent::Edit, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        attachment.copy_file(input)?;
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(trf!("К задаче \"{}\" прикреплено \"{}\"", "Attached \"{1}\" to task \"{0}\"", name, input), feedback))
+    }
+
+    pub fn add_note_with_hooks(&mut self, hooks: &Hooks, name: &str, text: &str) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let mut modified = self.tasks()[index].clone();
+        modified.add_note(text)?;
+
+        let (modified, feedback) = hooks.run(HookEvent::Edit, Some(&self.tasks()[index]), modified)?;
+        self.check_hook_rename(Some(index), &self.tasks()[index].name, &modified)?;
+        self.tasks_mut()[index] = modified;
+        Ok(with_feedback(trf!("К задаче \"{}\" добавлена заметка", "Note added to task \"{}\"", name), feedback))
+    }
+
+    pub fn remove_task_with_hooks(&mut self, hooks: &Hooks, name: &str) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let (_, feedback) = hooks.run(HookEvent::Remove, None, self.tasks()[index].clone())?;
+        self.remove_task(name).map(|message| with_feedback(message, feedback))
+    }
+
+    pub fn remove_at_with_hooks(&mut self, hooks: &Hooks, index: usize) -> Result<String, String> {
+        let (_, feedback) = hooks.run(HookEvent::Remove, None, self.tasks()[index].clone())?;
+        let task = self.tasks_mut().remove(index);
+        Ok(with_feedback(trf!("Задача \"{}\" удалена успешно", "Task \"{}\" removed", task.name), feedback))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use super::*;
+    use crate::Priority;
+
+    fn hooks_dir(name: &str, scripts: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("task_manager_hooks_{}_{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, body) in scripts {
+            let script = dir.join(file);
+            fs::write(&script, body).unwrap();
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn reports_veto_of_hook_that_ignores_input() {
+        let dir = hooks_dir("veto", &[("on-add-veto", "#!/bin/sh\necho \"veto: no\"\nexit 1\n")]);
+
+        let task = Task::new("a".to_owned(), "x".repeat(120 * 1024), Priority::Low);
+        let error = Hooks::new(Some(dir.clone())).run(HookEvent::Add, None, task).err().unwrap();
+        assert_eq!(error, format!("Хук \"{}\" отклонил изменение\nveto: no", dir.join("on-add-veto").display()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn streams_large_tasks_and_matches_exact_event() {
+        let dir = hooks_dir("stream", &[("on-add", "#!/bin/sh\nexec cat\n"), ("on-addition", "#!/bin/sh\nexit 1\n")]);
+
+        let task = Task::new("a".to_owned(), "x".repeat(120 * 1024), Priority::Low);
+        let (task, feedback) = Hooks::new(Some(dir.clone())).run(HookEvent::Add, None, task).unwrap();
+        assert_eq!(task.description.len(), 120 * 1024);
+        assert!(feedback.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_hook_rename_to_existing_name() {
+        let dir = hooks_dir("rename", &[("on-edit", "#!/bin/sh\ntail -n 1 | sed 's/\"name\":\"b\"/\"name\":\"a\"/'\n")]);
+        let hooks = Hooks::new(Some(dir.clone()));
+
+        let mut manager = TasksManager::new();
+        for name in ["a", "b"] {
+            manager.add_task(Task::new(name.to_owned(), String::new(), Priority::Low));
+        }
+        let error = manager.tag_at_with_hooks(&hooks, 1, "дом").unwrap_err();
+        assert_eq!(error, "Хук переименовал задачу \"b\" в \"a\", но задача с таким именем уже существует");
+        assert_eq!(manager.tasks()[1].name, "b");
+        assert!(manager.tasks()[1].tags.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
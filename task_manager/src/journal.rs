@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::trf;
+use crate::sync::{flatten, project_names, unflatten};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event {
+    Snapshot { time: DateTime<Local>, state: Value },
+    PutTask { time: DateTime<Local>, task: Value },
+    RemoveTask { time: DateTime<Local>, id: String },
+    AddProject { time: DateTime<Local>, name: String },
+    SetAutoArchive { time: DateTime<Local>, days: Value },
+}
+
+#[derive(Default)]
+struct Replay {
+    projects: Vec<String>,
+    tasks: Vec<(String, Value)>,
+    auto_archive_days: Value,
+}
+
+impl Replay {
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Snapshot { state, .. } => {
+                self.projects = project_names(&state);
+                self.tasks = flatten(&state);
+                self.auto_archive_days = state["auto_archive_days"].clone();
+            }
+            Event::PutTask { task, .. } => {
+                let id = task["id"].as_str().unwrap_or_default().to_owned();
+                match self.tasks.iter_mut().find(|(existing, _)| *existing == id) {
+                    Some((_, existing)) => *existing = task,
+                    None => self.tasks.push((id, task)),
+                }
+            }
+            Event::RemoveTask { id, .. } => self.tasks.retain(|(existing, _)| *existing != id),
+            Event::AddProject { name, .. } => {
+                if !self.projects.contains(&name) {
+                    self.projects.push(name);
+                }
+            }
+            Event::SetAutoArchive { days, .. } => self.auto_archive_days = days,
+        }
+    }
+}
+
+pub struct Journal {
+    pub state: Value,
+    pub events: usize,
+    pub valid_len: usize,
+}
+
+pub fn is_journal(data: &[u8]) -> bool {
+    data.starts_with(b"{\"event\":")
+}
+
+pub fn replay(data: &[u8]) -> Result<Journal, String> {
+    let mut replay = Replay::default();
+    let mut events = 0;
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let end = data[offset..].iter().position(|byte| *byte == b'\n').map_or(data.len(), |index| offset + index + 1);
+        let line = &data[offset..end];
+
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            match serde_json::from_slice::<Event>(line) {
+                Ok(event) => replay.apply(event),
+                Err(_) if data[end..].iter().all(u8::is_ascii_whitespace) => break,
+                Err(err) => return Err(trf!("Повреждено событие {} журнала: {}", "Journal event {} is corrupted: {}", events + 1, err)),
+            }
+            events += 1;
+        }
+        offset = end;
+    }
+
+    let state = unflatten(&replay.projects, replay.tasks.into_iter().map(|(_, task)| task), replay.auto_archive_days);
+    Ok(Journal { state, events, valid_len: offset })
+}
+
+fn encode(events: Vec<Event>) -> Result<Vec<u8>, String> {
+    let mut data = vec![];
+    for event in events {
+        serde_json::to_writer(&mut data, &event).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+        data.push(b'\n');
+    }
+    Ok(data)
+}
+
+pub fn snapshot(state: &Value) -> Result<Vec<u8>, String> {
+    encode(vec![Event::Snapshot { time: Local::now(), state: state.clone() }])
+}
+
+pub fn changes(old: &Value, new: &Value) -> Result<Vec<u8>, String> {
+    let time = Local::now();
+    let old_tasks: HashMap<String, Value> = flatten(old).into_iter().collect();
+    let new_tasks = flatten(new);
+    let mut events = vec![];
+
+    let old_projects = project_names(old);
+    for name in project_names(new) {
+        if !old_projects.contains(&name) {
+            events.push(Event::AddProject { time, name });
+        }
+    }
+
+    for (id, task) in &new_tasks {
+        if old_tasks.get(id) != Some(task) {
+            events.push(Event::PutTask { time, task: task.clone() });
+        }
+    }
+
+    for id in old_tasks.keys() {
+        if !new_tasks.iter().any(|(new_id, _)| new_id == id) {
+            events.push(Event::RemoveTask { time, id: id.clone() });
+        }
+    }
+
+    if old["auto_archive_days"] != new["auto_archive_days"] {
+        events.push(Event::SetAutoArchive { time, days: new["auto_archive_days"].clone() });
+    }
+
+    encode(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+    use crate::{Priority, Task, TasksManager};
+
+    fn temp_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("task_manager_journal_{}_{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn appends_changes_and_replays_them() {
+        let file = temp_file("append.jsonl");
+        let mut manager = TasksManager::new();
+        for name in ["a", "b"] {
+            manager.add_task(Task::new(name.to_owned(), String::new(), Priority::Low));
+        }
+        manager.compact_file(&file).unwrap();
+
+        let mut loaded = TasksManager::new();
+        loaded.read_from_file(&file, None).unwrap();
+        loaded.tasks_mut()[0].description = "изменена".to_owned();
+        loaded.remove_task("b").unwrap();
+        loaded.store_to_file(&file, None).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 3);
+
+        let mut replayed = TasksManager::new();
+        replayed.read_from_file(&file, None).unwrap();
+        assert_eq!(replayed.tasks().len(), 1);
+        assert_eq!(replayed.tasks()[0].description, "изменена");
+
+        assert_eq!(replayed.compact_file(&file).unwrap(), "Журнал сжат, событий заменено снимком: 3");
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 1);
+        let _ = fs::remove_dir_all(format!("{}.backups", file));
+        let _ = fs::remove_file(&file);
+    }
+
+    #[test]
+    fn tolerates_truncated_last_line() {
+        let file = temp_file("truncated.jsonl");
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        manager.compact_file(&file).unwrap();
+
+        let mut data = fs::read(&file).unwrap();
+        data.extend_from_slice(b"{\"event\":\"put_task\",\"ti");
+        fs::write(&file, &data).unwrap();
+
+        let mut loaded = TasksManager::new();
+        loaded.read_from_file(&file, None).unwrap();
+        assert_eq!(loaded.tasks()[0].name, "a");
+
+        loaded.tasks_mut()[0].name = "b".to_owned();
+        loaded.store_to_file(&file, None).unwrap();
+        let journal = replay(&fs::read(&file).unwrap()).unwrap();
+        assert_eq!(journal.events, 2);
+        assert_eq!(journal.valid_len, fs::metadata(&file).unwrap().len() as usize);
+
+        let mut corrupted = b"{\"event\":\"remove_task\"}\n".to_vec();
+        corrupted.extend_from_slice(&data);
+        assert!(replay(&corrupted).err().unwrap().starts_with("Повреждено событие 1 журнала"));
+        let _ = fs::remove_dir_all(format!("{}.backups", file));
+        let _ = fs::remove_file(&file);
+    }
+
+    #[test]
+    fn appends_after_last_line_without_newline() {
+        let file = temp_file("no_newline.jsonl");
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        manager.compact_file(&file).unwrap();
+        manager.add_task(Task::new("b".to_owned(), String::new(), Priority::Low));
+        manager.store_to_file(&file, None).unwrap();
+
+        let mut data = fs::read(&file).unwrap();
+        assert_eq!(data.pop(), Some(b'\n'));
+        fs::write(&file, &data).unwrap();
+
+        let mut loaded = TasksManager::new();
+        loaded.read_from_file(&file, None).unwrap();
+        loaded.add_task(Task::new("c".to_owned(), String::new(), Priority::Low));
+        loaded.store_to_file(&file, None).unwrap();
+
+        let mut replayed = TasksManager::new();
+        replayed.read_from_file(&file, None).unwrap();
+        let names: Vec<&str> = replayed.tasks().iter().map(|task| task.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        let _ = fs::remove_dir_all(format!("{}.backups", file));
+        let _ = fs::remove_file(&file);
+    }
+}
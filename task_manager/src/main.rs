@@ -1,25 +1,64 @@
-use std::fs::File;
-use std::io::{BufReader, Write};
+mod archive;
+mod attachments;
+mod backup;
+mod bulk;
+mod cli;
+mod config;
+mod crdt;
+mod crypto;
+mod diff;
+mod filter;
+mod history;
+mod hooks;
+mod journal;
+mod matching;
+mod notes;
+mod projects;
+mod quickadd;
+mod reminders;
+mod report;
+mod storage;
+mod sync;
+mod table;
+#[cfg(test)]
+mod tests;
+mod timer;
+mod users;
+mod workflow;
+
+use std::io::{BufRead, StdinLock, Stdout, Write};
 use std::path::Path;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use attachments::Attachment;
+use bulk::BulkAction;
+use config::{tr, trf, SortOrder};
+use history::{HistoryEntry, HistoryEvent};
+use hooks::Hooks;
+use notes::Note;
+use projects::{Project, DEFAULT_PROJECT};
+use storage::FileState;
+use sync::{GitSync, Pull, Side};
+use timer::TimeEntry;
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 enum Priority {
     Low,
     Medium,
     High
 }
 
-impl Priority {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Priority::Low => "Низкий".to_owned(),
-            Priority::Medium => "Средний".to_owned(),
-            Priority::High => "Высокий".to_owned()
+            Priority::Low => write!(f, "{}", tr("Низкий", "Low")),
+            Priority::Medium => write!(f, "{}", tr("Средний", "Medium")),
+            Priority::High => write!(f, "{}", tr("Высокий", "High"))
         }
     }
+}
 
+impl Priority {
     fn order(&self) -> u32 {
         match self {
             Priority::Low => 2,
@@ -27,326 +66,1400 @@ impl Priority {
             Priority::High => 0,
         }
     }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "high" | "1" | "высокий" => Some(Priority::High),
+            "medium" | "2" | "средний" => Some(Priority::Medium),
+            "low" | "3" | "низкий" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        let colors = &config::current().colors;
+        match self {
+            Priority::Low => colors.low,
+            Priority::Medium => colors.medium,
+            Priority::High => colors.high,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 struct Task {
+    #[serde(default = "generate_id")]
+    id: String,
     name: String,
     description: String,
     priority: Priority,
     add_time: DateTime<Local>,
-    completed: bool,
+    #[serde(alias = "completed", default = "workflow::initial_status", deserialize_with = "workflow::deserialize_status")]
+    status: String,
+    #[serde(default)]
+    completed_time: Option<DateTime<Local>>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    #[serde(default)]
+    time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    due_date: Option<DateTime<Local>>,
+    #[serde(default)]
+    reminder_lead_minutes: Option<u32>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    creator: Option<String>,
+    #[serde(default)]
+    assignee: Option<String>,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+    #[serde(default)]
+    notes: Vec<Note>,
 }
 
 impl Task {
     fn new(name: String, description: String, priority: Priority) -> Self {
-        Self {name, description, priority, add_time: Local::now(), completed: false}
+        let mut task = Self {
+            id: generate_id(),
+            name,
+            description,
+            priority,
+            add_time: Local::now(),
+            status: workflow::initial_status(),
+            completed_time: None,
+            history: vec![],
+            time_entries: vec![],
+            due_date: None,
+            reminder_lead_minutes: None,
+            tags: vec![],
+            creator: users::current_user().map(str::to_owned),
+            assignee: None,
+            attachments: vec![],
+            notes: vec![]
+        };
+        task.record(HistoryEvent::Created);
+        task
     }
 
-    fn new_from_console() -> Self {
-        let name = ConsoleTask::input("Введите имя задачи: ").unwrap();
-        let description = ConsoleTask::input("Введите описание задачи: ").unwrap();
-        let priority = match ConsoleTask::input("Введите индекс приоритета задачи (1 - высокий, 2 - средний, 3 - низкий): ").unwrap().as_str() {
-                "3" => Priority::Low,
-                "2" => Priority::Medium,
-                "1" => Priority::High,
-                _ => {
-                    println!("Неправильный приоритет, изменено на Низкий");
-                    Priority::Low
-                }
-            }; 
+    fn print_task(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{}: {} | {}: {} | {}: {} | {}: {}\n{}: \"{}\"",
+            tr("Название", "Name"),
+            self.name,
+            tr("Приоритет", "Priority"),
+            config::paint(&self.priority.to_string(), self.priority.color()),
+            tr("Статус", "Status"),
+            self.status,
+            tr("Дата и время", "Date and time"),
+            self.add_time.format(&config::current().date_format),
+            tr("Описание", "Description"),
+            self.description
+        )?;
 
-            Self::new(name, description, priority)
+        if self.creator.is_some() || self.assignee.is_some() {
+            writeln!(
+                out,
+                "{}: {} | {}: {}",
+                tr("Автор", "Creator"),
+                self.creator.as_deref().unwrap_or("-"),
+                tr("Исполнитель", "Assignee"),
+                self.assignee.as_deref().unwrap_or("-")
+            )?;
         }
 
-    fn print_task(&self) {
-        let status = if self.completed { "Выполнено" } else { "Не выполнено" };
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            writeln!(out, "{}: {}", tr("Теги", "Tags"), tags.join(" "))?;
+        }
 
-        println!(
-            "Название: {} | Приоритет: {} | Статус: {} | Дата и время: {}\nОписание: \"{}\"",
-            self.name,
-            self.priority.to_string(),
-            status,
-            self.add_time.format("%d-%m-%Y %H:%M:%S"),
-            self.description
-        );
+        if !self.attachments.is_empty() {
+            writeln!(out, "{}:", tr("Вложения", "Attachments"))?;
+            for (index, attachment) in self.attachments.iter().enumerate() {
+                let missing = if attachment.is_missing() {
+                    config::paint(tr(" (файл не найден)", " (file not found)"), config::current().colors.overdue)
+                } else {
+                    String::new()
+                };
+                writeln!(out, "  {}. {}{}", index + 1, attachment, missing)?;
+            }
+        }
+
+        if let Some(due_date) = self.due_date {
+            let overdue = if self.is_overdue() {
+                config::paint(tr(" (просрочено)", " (overdue)"), config::current().colors.overdue)
+            } else {
+                String::new()
+            };
+            writeln!(
+            out,
+                "{}: {}{}",
+                tr("Срок выполнения", "Due date"),
+                due_date.format(&config::current().date_format),
+                overdue
+            )?;
+        }
+
+        if !self.time_entries.is_empty() {
+            let running = if self.is_timer_running() { tr(" (таймер запущен)", " (timer running)") } else { "" };
+            writeln!(
+            out,
+                "{}: {}{}",
+                tr("Затрачено времени", "Time spent"),
+                report::format_duration(self.tracked_time()),
+                running
+            )?;
+        }
+
+        if !self.notes.is_empty() {
+            self.print_notes(out)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_edit(&mut self, updated_task: Task) {
+        self.record_edit("Название", self.name.clone(), updated_task.name.clone());
+        self.record_edit("Описание", self.description.clone(), updated_task.description.clone());
+        self.record_edit("Приоритет", self.priority.to_string(), updated_task.priority.to_string());
+        self.name = updated_task.name;
+        self.description = updated_task.description;
+        self.priority = updated_task.priority;
     }
 
-    fn mark_as_completed(&mut self) {
-        self.completed = true;
+    fn mark_as_completed(&mut self) -> Result<(), String> {
+        self.set_status(&config::current().workflow.done)
+    }
+
+    fn reopen(&mut self) -> Result<(), String> {
+        if !self.is_closed() {
+            return Err(trf!("Задача \"{}\" ещё не выполнена", "Task \"{}\" is not completed yet", self.name));
+        }
+        self.set_status(&config::current().workflow.initial)
     }
 }
 
+#[derive(Serialize, Deserialize)]
 struct TasksManager {
-    tasks: Vec<Task>
+    projects: Vec<Project>,
+    #[serde(skip)]
+    current: usize,
+    #[serde(default)]
+    auto_archive_days: Option<u32>,
+    #[serde(skip)]
+    file_state: Option<FileState>
 }
 
 impl TasksManager {
     fn new() -> Self {
-        Self { tasks: vec![] }
+        Self {
+            projects: vec![Project::new(DEFAULT_PROJECT.to_owned())],
+            current: 0,
+            auto_archive_days: None,
+            file_state: None
+        }
     }
 
+    fn tasks(&self) -> &Vec<Task> {
+        &self.projects[self.current].tasks
+    }
 
-    fn print_tasks(&mut self) {
-        if self.tasks.is_empty() {
-            println!("Нет задач.");
-            return;
-        }
-        
-        let mut tasks_by_priority: Vec<Vec<&Task>> = vec![vec![], vec![], vec![]];
-    
-        for task in &self.tasks {
-            let priority_order = task.priority.order() as usize;
-            tasks_by_priority[priority_order].push(task);
-        }
-    
-        for priority_tasks in tasks_by_priority.iter_mut() {
-            priority_tasks.sort_by(|a, b| a.add_time.cmp(&b.add_time));
+    fn tasks_mut(&mut self) -> &mut Vec<Task> {
+        &mut self.projects[self.current].tasks
+    }
+
+    fn print_tasks(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.tasks().is_empty() {
+            writeln!(out, "{}", tr("Нет задач.", "No tasks."))?;
+            return Ok(());
         }
+
+        writeln!(out, "{}", trf!("Проект \"{}\":", "Project \"{}\":", self.current_project()))?;
     
-        let mut task_number = 1; 
-    
-        for priority_tasks in tasks_by_priority.iter() {
-            for task in priority_tasks {
-                println!("{}. ", task_number);
-                task.print_task(); 
-                println!(); 
-                task_number += 1; 
-            }
+        for (index, task) in sort_for_display(self.tasks()).into_iter().enumerate() {
+            writeln!(out, "{}. ", index + 1)?;
+            task.print_task(out)?;
+            writeln!(out)?;
         }
+
+        Ok(())
     }
     
 
     fn add_task(&mut self, task: Task) {
-        self.tasks.push(task);
+        self.tasks_mut().push(task);
     }
 
     fn find_task(&self, name: &str) -> Option<usize> {
-        self.tasks.iter().position(|task| task.name == name)
+        self.tasks().iter().position(|task| task.name == name)
     }
 
     fn remove_task(&mut self, name: &str) -> Result<String, String> {
         if let Some(index) = self.find_task(name) {
-            self.tasks.remove(index);
-            Ok(format!("Задача \"{}\" удалена успешно", name))
+            self.tasks_mut().remove(index);
+            Ok(trf!("Задача \"{}\" удалена успешно", "Task \"{}\" removed", name))
         } else {
-            Err(format!("Задача с именем \"{}\" не существует", name))
+            Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name))
         }
     }
 
     fn edit_task(&mut self, name: &str, updated_task: Task) -> Result<String, String> {
+        if updated_task.name != name && self.find_task(&updated_task.name).is_some() {
+            return Err(trf!("Задача с именем \"{}\" уже существует", "Task \"{}\" already exists", updated_task.name));
+        }
+
         if let Some(index) = self.find_task(name) {
-            match self.tasks.get_mut(index) {
-                None => Err("Ошибка при доступе к задаче".to_owned()),
+            match self.tasks_mut().get_mut(index) {
+                None => Err(tr("Ошибка при доступе к задаче", "Error accessing task").to_owned()),
                 Some(task) => {
-                    task.name = updated_task.name;
-                    task.description = updated_task.description;
-                    task.priority = updated_task.priority;
-                    Ok(format!("Задача \"{}\" обновлена успешно", name))
+                    task.apply_edit(updated_task);
+                    Ok(trf!("Задача \"{}\" обновлена успешно", "Task \"{}\" updated", name))
                 }
             }
         } else {
-            Err(format!("Задача с именем \"{}\" не существует", name))
+            Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name))
         }
     }
 
-    fn store_to_file(&self, filename: &str) -> Result<String, String> {
-        if !Path::new(filename).exists() {
-            let file = match File::create(filename) {
-                Ok(file) => file,
-                Err(err) => return Err(format!("Ошибка при создании файла: {}", err))
-            };
+    fn store_to_file(&mut self, filename: &str, passphrase: Option<&str>) -> Result<String, String> {
+        if !Path::new(filename).exists() || self.is_source_file(filename) {
+            self.write_file(filename, passphrase, true)
+        } else {
+            Err(trf!("Файл \"{}\" уже существует", "File \"{}\" already exists", filename))
+        }
+    }
 
-            match serde_json::to_writer(&file, &self.tasks) {
-                Ok(_) => Ok("Данные успешно сохранены".to_owned()),
-                Err(err) => Err(format!("Ошибка при сохранении данных: {}", err))
+    fn read_from_file(&mut self, filename: &str, passphrase: Option<&str>) -> Result<String, String> {
+        if Path::new(filename).exists() {
+            let (manager, state) = Self::load_file(filename, passphrase)?;
+            *self = manager;
+            self.file_state = Some(state);
+
+            match self.auto_archive() {
+                Some(msg) => Ok(trf!("Данные успешно считаны\n{}", "Data loaded\n{}", msg)),
+                None => Ok(tr("Данные успешно считаны", "Data loaded").to_owned())
             }
         } else {
-            Err("Файл \"{filename}\" уже существует".to_owned())
+            Err(trf!("Файл \"{}\" не существует", "File \"{}\" does not exist", filename))
         }
     }
+}
 
-    fn read_from_file(&mut self, filename: &str) -> Result<String, String> {
-        if Path::new(filename).exists() {
-            let file = match File::open(filename) {
-                Ok(file) => file,
-                Err(err) => return Err(format!("Ошибка при создании файла {}", err))
-            };
+fn generate_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
 
-            let reader = BufReader::new(file);
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
 
-            self.tasks = match serde_json::from_reader(reader) {
-                Ok(data) => data,
-                Err(err) => {
-                    return Err(format!("Ошибка при чтении файла: {}", err));
-                }
-            };
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
 
-            Ok("Данные успешно считаны".to_owned())
-        } else {
-            Err(format!("Файл \"{}\" не существует", filename))
+fn sort_for_display(tasks: &[Task]) -> Vec<&Task> {
+    let mut sorted: Vec<&Task> = tasks.iter().collect();
+    match config::current().sort_order {
+        SortOrder::Priority => sorted.sort_by_key(|task| (task.priority.order(), task.add_time)),
+        SortOrder::Date => sorted.sort_by_key(|task| task.add_time),
+        SortOrder::Name => sorted.sort_by_key(|task| task.name.to_lowercase()),
+        SortOrder::Due => sorted.sort_by_key(|task| (task.due_date.is_none(), task.due_date, task.priority.order())),
+    }
+    sorted
+}
+
+fn prompt_password(query: &str) -> std::io::Result<String> {
+    match rpassword::prompt_password(query) {
+        Ok(password) => Ok(password),
+        Err(_) => {
+            print!("{}", query);
+            std::io::stdout().flush()?;
+
+            let mut buffer = String::new();
+            std::io::stdin().read_line(&mut buffer)?;
+            Ok(buffer.trim().to_owned())
         }
     }
 }
 
-struct ConsoleTask {
+struct ConsoleTask<R: BufRead, W: Write> {
     tasks_manager: TasksManager,
-    menu_options: Vec<String>
+    hooks: Hooks,
+    menu_options: Vec<String>,
+    reader: R,
+    writer: W,
+    interactive: bool,
+    sync: Option<GitSync>,
+    passphrase: Option<String>
 }
 
-impl ConsoleTask {
+impl ConsoleTask<StdinLock<'static>, Stdout> {
     fn new() -> Self {
+        let mut console = Self::with_io(
+            std::io::stdin().lock(),
+            std::io::stdout(),
+            Hooks::new(config::current().hooks_dir.clone())
+        );
+        console.interactive = true;
+        console
+    }
+}
+
+impl<R: BufRead, W: Write> ConsoleTask<R, W> {
+    fn with_io(reader: R, writer: W, hooks: Hooks) -> Self {
         Self {
             tasks_manager: TasksManager::new(),
+            hooks,
             menu_options: vec![
-                "Добавить задачу".to_owned(),
-                "Найти задачу".to_owned(),
-                "Изменить задачу".to_owned(),
-                "Удалить задачу".to_owned(),
-                "Вывести задачи".to_owned(),
-                "Сохранить задачи в файл".to_owned(),
-                "Считать задачи из файла".to_owned(),
-                "Отметить задачу как выполненную".to_owned()
-            ]
+                tr("Добавить задачу", "Add task").to_owned(),
+                tr("Найти задачу", "Find task").to_owned(),
+                tr("Изменить задачу", "Edit task").to_owned(),
+                tr("Удалить задачу", "Remove task").to_owned(),
+                tr("Вывести задачи", "List tasks").to_owned(),
+                tr("Сохранить задачи в файл", "Save tasks to file").to_owned(),
+                tr("Считать задачи из файла", "Load tasks from file").to_owned(),
+                tr("Отметить задачу как выполненную", "Mark task as completed").to_owned(),
+                tr("Отчёт о продуктивности", "Productivity report").to_owned(),
+                tr("Отметить задачу как невыполненную", "Mark task as not completed").to_owned(),
+                tr("Показать историю задачи", "Show task history").to_owned(),
+                tr("Запустить таймер задачи", "Start task timer").to_owned(),
+                tr("Остановить таймер", "Stop timer").to_owned(),
+                tr("Табель учёта времени", "Timesheet").to_owned(),
+                tr("Создать проект", "Create project").to_owned(),
+                tr("Переключить проект", "Switch project").to_owned(),
+                tr("Переместить задачу в другой проект", "Move task to another project").to_owned(),
+                tr("Вывести задачи всех проектов", "List tasks of all projects").to_owned(),
+                tr("Переместить выполненные задачи в архив", "Archive completed tasks").to_owned(),
+                tr("Просмотреть архив", "Browse archive").to_owned(),
+                tr("Поиск в архиве", "Search archive").to_owned(),
+                tr("Восстановить задачу из архива", "Restore task from archive").to_owned(),
+                tr("Настроить автоматическую архивацию", "Configure automatic archiving").to_owned(),
+                tr("Установить срок выполнения задачи", "Set task due date").to_owned(),
+                tr("Вывести задачи в виде таблицы", "List tasks as a table").to_owned(),
+                tr("Массовые операции с задачами", "Bulk operations on tasks").to_owned(),
+                tr("Быстрое добавление задачи одной строкой", "Quick-add task in one line").to_owned(),
+                tr("Изменить статус задачи", "Change task status").to_owned(),
+                tr("Доска задач", "Task board").to_owned(),
+                tr("Мои задачи", "My tasks").to_owned(),
+                tr("Назначить исполнителя задачи", "Assign task").to_owned(),
+                tr("Синхронизировать с git", "Sync with git").to_owned(),
+                tr("Прикрепить файл или ссылку к задаче", "Attach file or link to task").to_owned(),
+                tr("Открыть вложение задачи", "Open task attachment").to_owned(),
+                tr("Добавить заметку к задаче", "Add note to task").to_owned()
+            ],
+            reader,
+            writer,
+            interactive: false,
+            sync: None,
+            passphrase: None
         }
     }
-    
-    fn print_menu(&self) {
+
+    fn print_menu(&mut self) -> std::io::Result<()> {
         for (index, menu_option) in self.menu_options.iter().enumerate() {
-            println!("{}. {}", index + 1, menu_option);
+            writeln!(self.writer, "{}. {}", index + 1, menu_option)?;
         }
+        Ok(())
     }
 
-    fn input(query: &str) -> std::io::Result<String> {
-        print!("{}", query);
-        std::io::stdout().flush()?;
+    fn input(&mut self, query: &str) -> std::io::Result<String> {
+        write!(self.writer, "{}", query)?;
+        self.writer.flush()?;
 
         let mut buffer = String::new();
-        std::io::stdin().read_line(&mut buffer)?;
+        if self.reader.read_line(&mut buffer)? == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, tr("ввод завершён", "end of input")));
+        }
         Ok(buffer.trim().to_owned())
     }
 
-    fn process_command(&mut self) {
-        match Self::input("\nВведите индекс команды: ") {
-            Ok(command) => {
-                match command.as_str() {
-                    "1" => {
-                        self.tasks_manager.add_task(Task::new_from_console());
+    fn input_password(&mut self, query: &str) -> std::io::Result<String> {
+        if self.interactive {
+            if let Ok(password) = rpassword::prompt_password(query) {
+                return Ok(password);
+            }
+        }
+        self.input(query)
+    }
+
+    fn input_task(&mut self, query: &str) -> Result<String, String> {
+        let input = self.input(query).map_err(|err| trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+        let index = self.tasks_manager.resolve_task(input.as_str())?;
+        Ok(self.tasks_manager.tasks()[index].name.clone())
+    }
+
+    fn input_new_task(&mut self) -> std::io::Result<Task> {
+        let name = self.input(tr("Введите имя задачи: ", "Enter task name: "))?;
+        let description = self.input(tr("Введите описание задачи: ", "Enter task description: "))?;
+        let priority = match self.input(tr("Введите индекс приоритета задачи (1 - высокий, 2 - средний, 3 - низкий): ", "Enter task priority index (1 - high, 2 - medium, 3 - low): "))?.as_str() {
+            "3" => Priority::Low,
+            "2" => Priority::Medium,
+            "1" => Priority::High,
+            _ => {
+                let priority = config::current().default_priority.clone();
+                writeln!(self.writer, "{}", trf!("Неправильный приоритет, изменено на {}", "Invalid priority, changed to {}", priority))?;
+                priority
+            }
+        };
+
+        Ok(Task::new(name, description, priority))
+    }
+
+    fn input_filename(&mut self, query: &str) -> std::io::Result<String> {
+        match config::current().data_file.as_deref() {
+            Some(data_file) => {
+                let filename = self.input(&trf!("{} (пусто - {}): ", "{} (empty - {}): ", query, data_file))?;
+                Ok(if filename.is_empty() { data_file.to_owned() } else { filename })
+            }
+            None => self.input(&format!("{}: ", query))
+        }
+    }
+
+    fn load_file(&mut self, filename: &str) -> std::io::Result<()> {
+        let passphrase = if crypto::is_encrypted_file(filename) {
+            match self.input_password(tr("Файл зашифрован, введите пароль: ", "File is encrypted, enter password: ")) {
+                Ok(passphrase) => Some(passphrase),
+                Err(err) => {
+                    writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                    return Ok(());
+                }
+            }
+        } else {
+            None
+        };
+
+        match self.tasks_manager.read_from_file(filename, passphrase.as_deref()) {
+            Ok(msg) => {
+                self.passphrase = passphrase;
+                writeln!(self.writer, "{}", msg)
+            }
+            Err(msg) => writeln!(self.writer, "{}", msg),
+        }
+    }
+
+    fn enable_sync(&mut self, filename: &str) -> std::io::Result<()> {
+        match GitSync::open(filename, &self.tasks_manager) {
+            Ok(sync) => self.sync = Some(sync),
+            Err(msg) => writeln!(self.writer, "{}", msg)?,
+        }
+        Ok(())
+    }
+
+    fn commit_changes(&mut self) -> std::io::Result<()> {
+        if let Some(sync) = &mut self.sync {
+            match sync.commit(&mut self.tasks_manager) {
+                Ok(Some(message)) => writeln!(self.writer, "{}", trf!("Изменения записаны в git: {}", "Changes committed to git: {}", message.lines().next().unwrap_or_default()))?,
+                Ok(None) => {}
+                Err(msg) => writeln!(self.writer, "{}", msg)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn sync_with_remote(&mut self, sync: &mut GitSync) -> std::io::Result<()> {
+        let mut merge = match sync.pull(&mut self.tasks_manager) {
+            Ok(Pull::Merge(merge)) => merge,
+            Ok(Pull::NoUpstream) => {
+                writeln!(self.writer, "{}", tr("Удалённая ветка не настроена, изменения будут отправлены в origin", "No upstream branch, changes will be pushed to origin"))?;
+                return self.push(sync);
+            }
+            Ok(Pull::UpToDate) => {
+                writeln!(self.writer, "{}", tr("Новых изменений в удалённом репозитории нет", "No new changes in the remote repository"))?;
+                return self.push(sync);
+            }
+            Ok(Pull::FastForward) => {
+                writeln!(self.writer, "{}", tr("Получены изменения из удалённого репозитория", "Received changes from the remote repository"))?;
+                return self.push(sync);
+            }
+            Err(msg) => {
+                writeln!(self.writer, "{}", msg)?;
+                return Ok(());
+            }
+        };
+
+        for index in 0..merge.conflicts.len() {
+            writeln!(self.writer, "{}", merge.conflicts[index].describe())?;
+            let side = loop {
+                match self.input(tr("Оставить версию (1 - нашу, 2 - из удалённого репозитория): ", "Keep version (1 - ours, 2 - from the remote repository): "))?.as_str() {
+                    "1" => break Side::Ours,
+                    "2" => break Side::Theirs,
+                    _ => writeln!(self.writer, "{}", tr("Неверный ввод", "Invalid input"))?,
+                }
+            };
+            merge.resolve(index, side);
+        }
+
+        match sync.finish(&mut self.tasks_manager, merge) {
+            Ok(msg) => {
+                writeln!(self.writer, "{}", msg)?;
+                self.push(sync)
+            }
+            Err(msg) => writeln!(self.writer, "{}", msg),
+        }
+    }
+
+    fn push(&mut self, sync: &GitSync) -> std::io::Result<()> {
+        match sync.push() {
+            Ok(msg) => writeln!(self.writer, "{}", msg),
+            Err(msg) => writeln!(self.writer, "{}", msg),
+        }
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        self.print_menu()?;
+
+        loop {
+            self.process_command()?;
+            self.commit_changes()?;
+        }
+    }
+
+    fn process_command(&mut self) -> std::io::Result<()> {
+        let command = self.input(tr("\nВведите индекс команды: ", "\nEnter command index: "))?;
+
+        match command.as_str() {
+            "1" => {
+                let task = match self.input_new_task() {
+                    Ok(task) => task,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.add_task_with_hooks(&self.hooks, task) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "2" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для поиска: ", "Enter name or number of the task to find: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.find_task(name.as_str()) {
+                    None => writeln!(self.writer, "{}", self.tasks_manager.not_found_message(name.as_str()))?,
+                    Some(index) => {
+                        writeln!(self.writer, "{}", tr("Задача найдена!", "Task found!"))?;
+                        self.tasks_manager.tasks().get(index).unwrap().print_task(&mut self.writer)?;
+                    }
+                }
+            }
+
+            "3" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для изменения: ", "Enter name or number of the task to edit: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let task = match self.input_new_task() {
+                    Ok(task) => task,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.edit_task_with_hooks(&self.hooks, name.as_str(), task) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "4" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для удаления: ", "Enter name or number of the task to remove: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.remove_task_with_hooks(&self.hooks, name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "5" => {
+                self.tasks_manager.print_tasks(&mut self.writer)?;
+            }
+
+            "6" => {
+                let filename = match self.input_filename(tr("Введите имя файла для сохранения туда данных", "Enter file name to save data to")) {
+                    Ok(filename) => filename,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
                     }
+                };
 
-                    "2" => {
-                        let name = match Self::input("Введите имя задачи для поиска: ") {
-                            Ok(name) => name,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
+                let mut overwrite = false;
+
+                if self.tasks_manager.is_modified_externally(filename.as_str()) {
+                    writeln!(self.writer, "{}", trf!("Файл \"{}\" был изменён другим процессом после последней загрузки", "File \"{}\" was modified by another process since it was last loaded", filename))?;
+
+                    let choice = match self.input(tr("1 - перезагрузить файл (локальные изменения будут потеряны), 2 - объединить изменения, 3 - перезаписать файл, иначе - отмена: ", "1 - reload the file (local changes will be lost), 2 - merge changes, 3 - overwrite the file, otherwise - cancel: ")) {
+                        Ok(choice) => choice,
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
+                        }
+                    };
+
+                    match choice.as_str() {
+                        "1" | "2" => {
+                            let passphrase = if crypto::is_encrypted_file(filename.as_str()) {
+                                match self.input_password(tr("Файл зашифрован, введите пароль: ", "File is encrypted, enter password: ")) {
+                                    Ok(passphrase) => Some(passphrase),
+                                    Err(err) => {
+                                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                                        return Ok(());
+                                    }
+                                }
+                            } else {
+                                None
+                            };
+
+                            let result = if choice == "1" {
+                                self.tasks_manager.read_from_file(filename.as_str(), passphrase.as_deref())
+                            } else {
+                                self.tasks_manager.merge_from_file(filename.as_str(), passphrase.as_deref())
+                            };
+
+                            match result {
+                                Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                                Err(msg) => {
+                                    writeln!(self.writer, "{}", msg)?;
+                                    return Ok(());
+                                }
                             }
-                        };
 
-                        match self.tasks_manager.find_task(name.as_str()) {
-                            None => println!("Задача с именем \"{}\" не существует", name),
-                            Some(index) => {
-                                println!("Задача найдена!");
-                                self.tasks_manager.tasks.get(index).unwrap().print_task();
+                            if choice == "1" {
+                                self.passphrase = passphrase;
+                                return Ok(());
                             }
                         }
+                        "3" => overwrite = true,
+                        _ => {
+                            writeln!(self.writer, "{}", tr("Сохранение отменено", "Save cancelled"))?;
+                            return Ok(());
+                        }
                     }
+                }
 
-                    "3" => {
-                        let name = match Self::input("Введите имя задачи для изменения: ") {
-                            Ok(name) => name,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
+                let passphrase = match self.passphrase.clone() {
+                    Some(passphrase) => match self.input(tr("Файл был зашифрован. Сохранить его без шифрования? (д/н): ", "The file was encrypted. Save it without encryption? (y/n): ")) {
+                        Ok(answer) if answer == "д" || answer == "y" => None,
+                        Ok(_) => Some(passphrase),
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
+                        }
+                    },
+                    None => match self.input(tr("Зашифровать файл паролем? (д/н): ", "Encrypt the file with a password? (y/n): ")) {
+                        Ok(answer) if answer == "д" || answer == "y" => {
+                            let passphrase = match self.input_password(tr("Введите пароль: ", "Enter password: ")) {
+                                Ok(passphrase) => passphrase,
+                                Err(err) => {
+                                    writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                                    return Ok(());
+                                }
+                            };
+
+                            match self.input_password(tr("Повторите пароль: ", "Repeat password: ")) {
+                                Ok(confirmation) if confirmation == passphrase => {}
+                                Ok(_) => {
+                                    writeln!(self.writer, "{}", tr("Пароли не совпадают", "Passwords do not match"))?;
+                                    return Ok(());
+                                }
+                                Err(err) => {
+                                    writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                                    return Ok(());
+                                }
                             }
-                        };
 
-                        match self.tasks_manager.edit_task(name.as_str(), Task::new_from_console()) {
-                            Ok(msg) => println!("{}", msg),
-                            Err(msg) => println!("{}", msg),
+                            if passphrase.is_empty() {
+                                writeln!(self.writer, "{}", tr("Пароль не может быть пустым", "Password cannot be empty"))?;
+                                return Ok(());
+                            }
+
+                            Some(passphrase)
+                        }
+                        Ok(_) => None,
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
                         }
+                    },
+                };
+
+                let result = if overwrite {
+                    self.tasks_manager.write_file(filename.as_str(), passphrase.as_deref(), false)
+                } else {
+                    self.tasks_manager.store_to_file(filename.as_str(), passphrase.as_deref())
+                };
+
+                match result {
+                    Ok(msg) => {
+                        self.passphrase = passphrase;
+                        writeln!(self.writer, "{}", msg)?
                     }
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
 
-                    "4" => {
-                        let name = match Self::input("Введите имя задачи для удаления: ") {
-                            Ok(name) => name,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
-                            }
-                        };
+            "7" => {
+                let filename = match self.input_filename(tr("Введите имя файла для чтения задач из него", "Enter file name to load tasks from")) {
+                    Ok(filename) => filename,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
 
-                        match self.tasks_manager.remove_task(name.as_str()) {
-                            Ok(msg) => println!("{}", msg),
-                            Err(msg) => println!("{}", msg),
-                        }
+                self.load_file(filename.as_str())?;
+            }
+
+            "8" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для отметки как выполненной: ", "Enter name or number of the task to mark as completed: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
                     }
+                };
+
+                match self.tasks_manager.complete_task_with_hooks(&self.hooks, name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }        
 
-                    "5" => {
-                        self.tasks_manager.print_tasks();
+            "9" => {
+                let report = self.tasks_manager.report();
+
+                match self.input(tr("Введите формат отчёта (1 - текст, 2 - JSON): ", "Enter report format (1 - text, 2 - JSON): ")) {
+                    Ok(format) if format == "2" => match report.to_json() {
+                        Ok(json) => writeln!(self.writer, "{}", json)?,
+                        Err(msg) => writeln!(self.writer, "{}", msg)?,
+                    },
+                    Ok(_) => write!(self.writer, "{}", report.to_text())?,
+                    Err(err) => writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?,
+                }
+            }
+
+            "10" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для отметки как невыполненной: ", "Enter name or number of the task to mark as not completed: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
                     }
+                };
 
-                    "6" => {
-                        let filename = match Self::input("Введите имя файла для сохранения туда данных: ") {
-                            Ok(filename) => filename,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
-                            }
-                        };
+                match self.tasks_manager.find_task(name.as_str()) {
+                    None => writeln!(self.writer, "{}", trf!("Задачи с именем \"{}\" не существует", "Task \"{}\" does not exist", name))?,
+                    Some(index) => match self.tasks_manager.tasks_mut()[index].reopen() {
+                        Ok(_) => writeln!(self.writer, "{}", trf!("Задача \"{}\" отмечена как невыполненная", "Task \"{}\" marked as not completed", name))?,
+                        Err(msg) => writeln!(self.writer, "{}", msg)?,
+                    },
+                }
+            }
+
+            "11" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для просмотра истории: ", "Enter name or number of the task to show history for: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.find_task(name.as_str()) {
+                    None => writeln!(self.writer, "{}", trf!("Задачи с именем \"{}\" не существует", "Task \"{}\" does not exist", name))?,
+                    Some(index) => self.tasks_manager.tasks()[index].print_history(&mut self.writer)?,
+                }
+            }
+
+            "12" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для запуска таймера: ", "Enter name or number of the task to start the timer for: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.start_timer(name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "13" => {
+                match self.tasks_manager.stop_timer() {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "14" => {
+                write!(self.writer, "{}", self.tasks_manager.timesheet().to_text())?;
+            }
+
+            "15" => {
+                let name = match self.input(tr("Введите имя нового проекта: ", "Enter new project name: ")) {
+                    Ok(name) => name,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.create_project(name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "16" => {
+                self.tasks_manager.print_projects(&mut self.writer)?;
+
+                let name = match self.input(tr("Введите имя проекта: ", "Enter project name: ")) {
+                    Ok(name) => name,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.switch_project(name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "17" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для перемещения: ", "Enter name or number of the task to move: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let project = match self.input(tr("Введите имя проекта, в который нужно переместить задачу: ", "Enter the project to move the task to: ")) {
+                    Ok(project) => project,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.move_task(name.as_str(), project.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "18" => {
+                self.tasks_manager.print_all_tasks(&mut self.writer)?;
+            }
+
+            "19" => {
+                let name = match self.input(tr("Введите имя или номер выполненной задачи (пусто - все выполненные задачи): ", "Enter name or number of a completed task (empty - all completed tasks): ")) {
+                    Ok(name) => name,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                if name.is_empty() {
+                    let archived = self.tasks_manager.archive_completed(0);
+                    writeln!(self.writer, "{}", trf!("Перемещено в архив задач: {}", "Tasks moved to archive: {}", archived))?;
+                } else {
+                    let result = self.tasks_manager
+                        .resolve_task(name.as_str())
+                        .and_then(|index| self.tasks_manager.archive_task(&self.tasks_manager.tasks()[index].name.clone()));
+                    match result {
+                        Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                        Err(msg) => writeln!(self.writer, "{}", msg)?,
+                    }
+                }
+            }
+
+            "20" => {
+                self.tasks_manager.print_archive(&mut self.writer, "")?;
+            }
+
+            "21" => {
+                let query = match self.input(tr("Введите текст для поиска в архиве: ", "Enter text to search the archive for: ")) {
+                    Ok(query) => query,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                self.tasks_manager.print_archive(&mut self.writer, query.as_str())?;
+            }
+
+            "22" => {
+                let name = match self.input(tr("Введите имя задачи для восстановления из архива: ", "Enter name of the task to restore from archive: ")) {
+                    Ok(name) => name,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.restore_task(name.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "23" => {
+                let days = match self.input(tr("Через сколько дней после выполнения перемещать задачи в архив (0 - отключить): ", "Archive tasks how many days after completion (0 - disable): ")) {
+                    Ok(days) => days,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
 
-                        match self.tasks_manager.store_to_file(filename.as_str()) {
-                            Ok(msg) => println!("{}", msg),
-                            Err(msg) => println!("{}", msg),
+                match days.parse::<u32>() {
+                    Ok(0) => {
+                        self.tasks_manager.auto_archive_days = None;
+                        writeln!(self.writer, "{}", tr("Автоматическая архивация отключена", "Automatic archiving disabled"))?;
+                    }
+                    Ok(days) => {
+                        self.tasks_manager.auto_archive_days = Some(days);
+                        writeln!(self.writer, "{}", trf!("Выполненные задачи будут перемещаться в архив через {} дн.", "Completed tasks will be archived after {} days", days))?;
+                        if let Some(msg) = self.tasks_manager.auto_archive() {
+                            writeln!(self.writer, "{}", msg)?;
                         }
                     }
+                    Err(_) => writeln!(self.writer, "{}", tr("Неправильное количество дней", "Invalid number of days"))?,
+                }
+            }
 
-                    "7" => {
-                        let filename = match Self::input("Введите имя файла для чтения задач из него: ") {
-                            Ok(filename) => filename,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
-                            }
-                        };
+            "24" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для установки срока: ", "Enter name or number of the task to set the due date for: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
 
-                        match self.tasks_manager.read_from_file(filename.as_str()) {
-                            Ok(msg) => println!("{}", msg),
-                            Err(msg) => println!("{}", msg),
+                let due_date = match self.input(tr("Введите срок выполнения (дд-мм-гггг чч:мм, пусто - без срока): ", "Enter due date (dd-mm-yyyy hh:mm, empty - no due date): ")) {
+                    Ok(due_date) if due_date.is_empty() => None,
+                    Ok(due_date) => match reminders::parse_due_date(due_date.as_str()) {
+                        Ok(due_date) => Some(due_date),
+                        Err(msg) => {
+                            writeln!(self.writer, "{}", msg)?;
+                            return Ok(());
                         }
+                    },
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let lead = match self.input(tr("За сколько минут напомнить о сроке (пусто - по умолчанию): ", "Remind how many minutes before the due date (empty - default): ")) {
+                    Ok(lead) if lead.is_empty() => None,
+                    Ok(lead) => match lead.parse::<u32>() {
+                        Ok(lead) => Some(lead),
+                        Err(_) => {
+                            writeln!(self.writer, "{}", tr("Неправильное время напоминания", "Invalid reminder time"))?;
+                            return Ok(());
+                        }
+                    },
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.set_due_date(name.as_str(), due_date, lead) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "25" => {
+                let project = self.tasks_manager.current_project();
+                let tasks: Vec<(usize, &str, &Task)> = sort_for_display(self.tasks_manager.tasks())
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, task)| (index + 1, project, task))
+                    .collect();
+                table::print_table(&mut self.writer, &tasks, false)?;
+            }
+
+            "26" => {
+                let selection = match self.input(tr("Введите номера задач (например 1,3,5-7) или фильтр (например #дом priority:high status:open): ", "Enter task numbers (e.g. 1,3,5-7) or a filter (e.g. #home priority:high status:open): ")) {
+                    Ok(selection) => selection,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let project = self.tasks_manager.current_project();
+                let selected: Vec<(usize, &str, &Task)> = match self.tasks_manager.select(selection.as_str()) {
+                    Ok(selected) => selected.into_iter().map(|(number, task)| (number, project, task)).collect(),
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
                     }
+                };
 
-                    "8" => {
-                        let name = match Self::input("Введите имя задачи для отметки как выполненной: ") {
-                            Ok(name) => name,
-                            Err(err) => {
-                                println!("Ошибка при получении ввода пользователя: {}", err);
-                                return;
+                if selected.is_empty() {
+                    writeln!(self.writer, "{}", tr("Нет подходящих задач", "No matching tasks"))?;
+                    return Ok(());
+                }
+
+                table::print_table(&mut self.writer, &selected, false)?;
+                let ids: Vec<String> = selected.iter().map(|(_, _, task)| task.id.clone()).collect();
+
+                let action = match self.input(tr("1 - отметить как выполненные, 2 - удалить, 3 - изменить приоритет, 4 - добавить тег: ", "1 - mark as completed, 2 - remove, 3 - change priority, 4 - add tag: ")) {
+                    Ok(action) => action,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let action = match action.as_str() {
+                    "1" => BulkAction::Complete,
+                    "2" => BulkAction::Delete,
+                    "3" => match self.input(tr("Введите индекс приоритета (1 - высокий, 2 - средний, 3 - низкий): ", "Enter priority index (1 - high, 2 - medium, 3 - low): ")) {
+                        Ok(priority) => match Priority::from_name(priority.as_str()) {
+                            Some(priority) => BulkAction::SetPriority(priority),
+                            None => {
+                                writeln!(self.writer, "{}", tr("Неправильный приоритет", "Invalid priority"))?;
+                                return Ok(());
                             }
-                        };
-        
-                        match self.tasks_manager.find_task(name.as_str()) {
-                            None => println!("Задачи с именем \"{}\" не существует", name),
-                            Some(index) => {
-                                self.tasks_manager.tasks[index].mark_as_completed();
-                                println!("Задача \"{}\" отмечена как выполненная", name);
+                        },
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
+                        }
+                    },
+                    "4" => match self.input(tr("Введите тег: ", "Enter tag: ")) {
+                        Ok(tag) if !tag.trim_start_matches('#').is_empty() => BulkAction::AddTag(tag.trim_start_matches('#').to_owned()),
+                        Ok(_) => {
+                            writeln!(self.writer, "{}", tr("Тег не может быть пустым", "Tag cannot be empty"))?;
+                            return Ok(());
+                        }
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
+                        }
+                    },
+                    _ => {
+                        writeln!(self.writer, "{}", tr("Неверный ввод команды", "Invalid command"))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.input(&trf!("Применить к задачам ({})? (д/н): ", "Apply to tasks ({})? (y/n): ", ids.len())) {
+                    Ok(answer) if answer == "д" || answer == "y" => {
+                        for result in self.tasks_manager.apply_bulk(&self.hooks, &ids, &action) {
+                            match result {
+                                Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                                Err(msg) => writeln!(self.writer, "{}", msg)?,
                             }
                         }
-                    }        
+                    }
+                    Ok(_) => writeln!(self.writer, "{}", tr("Операция отменена", "Operation cancelled"))?,
+                    Err(err) => writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?,
+                }
+            }
+
+            "27" => {
+                let line = match self.input(tr("Введите задачу (например: Купить молоко !high #дом due:tomorrow \"описание\"): ", "Enter task (e.g.: Buy milk !high #home due:tomorrow \"description\"): ")) {
+                    Ok(line) => line,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let task = match quickadd::parse_quick_add(line.as_str(), Local::now()) {
+                    Ok(task) => task,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.add_task_with_hooks(&self.hooks, task) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "28" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для изменения статуса: ", "Enter name or number of the task to change status: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let workflow = &config::current().workflow;
+                let current = &self.tasks_manager.tasks()[self.tasks_manager.find_task(name.as_str()).unwrap_or_default()].status;
+                let next_states = workflow.next_states(current);
+                if next_states.is_empty() {
+                    writeln!(self.writer, "{}", trf!("Из статуса \"{}\" нет разрешённых переходов", "No transitions allowed from status \"{}\"", current))?;
+                    return Ok(());
+                }
+
+                let choices: Vec<String> = next_states
+                    .iter()
+                    .enumerate()
+                    .map(|(index, state)| format!("{} - {}", index + 1, state))
+                    .collect();
+                let query = trf!("Текущий статус: \"{}\". Новый статус ({}): ", "Current status: \"{}\". New status ({}): ", current, choices.join(", "));
+
+                let status = match self.input(&query) {
+                    Ok(status) => status,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let status = match status.parse::<usize>() {
+                    Ok(number) => number.checked_sub(1).and_then(|index| next_states.get(index)).copied(),
+                    Err(_) => workflow.find_state(status.as_str()),
+                };
+
+                match status {
+                    Some(status) => match self.tasks_manager.set_status_with_hooks(&self.hooks, name.as_str(), status) {
+                        Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                        Err(msg) => writeln!(self.writer, "{}", msg)?,
+                    },
+                    None => writeln!(self.writer, "{}", tr("Неизвестный статус", "Unknown status"))?,
+                }
+            }
+
+            "29" => {
+                self.tasks_manager.print_board(&mut self.writer)?;
+            }
+
+            "30" => {
+                let user = match users::current_user() {
+                    Some(user) => user,
+                    None => {
+                        writeln!(self.writer, "{}", tr("Текущий пользователь не задан, укажите параметр user в настройках", "Current user is not set, specify the user setting"))?;
+                        return Ok(());
+                    }
+                };
+
+                writeln!(self.writer, "{}", trf!("Задачи пользователя {}:", "Tasks of user {}:", user))?;
+                let tasks = self.tasks_manager.assigned_tasks(user);
+                table::print_table(&mut self.writer, &tasks, true)?;
+            }
+
+            "31" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи для назначения: ", "Enter name or number of the task to assign: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let query = match users::current_user() {
+                    Some(user) => trf!("Введите исполнителя (пусто - снять исполнителя, \"я\" - {}): ", "Enter assignee (empty - unassign, \"me\" - {}): ", user),
+                    None => tr("Введите исполнителя (пусто - снять исполнителя): ", "Enter assignee (empty - unassign): ").to_owned(),
+                };
+
+                let assignee = match self.input(&query) {
+                    Ok(assignee) if assignee.is_empty() => None,
+                    Ok(assignee) if assignee == "я" || assignee == "me" => users::current_user().map(str::to_owned).or(Some(assignee)),
+                    Ok(assignee) => Some(assignee),
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.assign_task_with_hooks(&self.hooks, name.as_str(), assignee) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "32" => {
+                let mut sync = match self.sync.take() {
+                    Some(sync) => sync,
+                    None => {
+                        writeln!(self.writer, "{}", tr("Синхронизация не включена, укажите sync = \"git\" в настройках", "Sync is not enabled, set sync = \"git\" in the settings"))?;
+                        return Ok(());
+                    }
+                };
+
+                let result = self.sync_with_remote(&mut sync);
+                self.sync = Some(sync);
+                result?;
+            }
+
+            "33" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи: ", "Enter task name or number: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let input = match self.input(tr("Введите путь к файлу или URL: ", "Enter file path or URL: ")) {
+                    Ok(input) => input,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                let copy = if attachments::is_link(&input) {
+                    false
+                } else {
+                    match self.input(tr("Скопировать файл в каталог вложений? (д/н): ", "Copy the file to the attachments directory? (y/n): ")) {
+                        Ok(answer) => answer == "д" || answer == "y",
+                        Err(err) => {
+                            writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                            return Ok(());
+                        }
+                    }
+                };
+
+                match self.tasks_manager.attach_with_hooks(&self.hooks, name.as_str(), input.as_str(), copy) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            "34" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи: ", "Enter task name or number: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let attachments = match self.tasks_manager.find_task(&name) {
+                    Some(index) => self.tasks_manager.tasks()[index].attachments.clone(),
+                    None => vec![],
+                };
+                if attachments.is_empty() {
+                    writeln!(self.writer, "{}", trf!("У задачи \"{}\" нет вложений", "Task \"{}\" has no attachments", name))?;
+                    return Ok(());
+                }
 
-                    _ => println!("Неверный ввод команды")
+                for (index, attachment) in attachments.iter().enumerate() {
+                    let missing = if attachment.is_missing() { tr(" (файл не найден)", " (file not found)") } else { "" };
+                    writeln!(self.writer, "{}. {}{}", index + 1, attachment, missing)?;
+                }
+
+                let number = match self.input(tr("Введите номер вложения: ", "Enter attachment number: ")) {
+                    Ok(number) => number,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+                match number.parse::<usize>().ok().and_then(|number| number.checked_sub(1)).and_then(|index| attachments.get(index)) {
+                    Some(attachment) => match attachment.open() {
+                        Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                        Err(msg) => writeln!(self.writer, "{}", msg)?,
+                    },
+                    None => writeln!(self.writer, "{}", tr("Неправильный номер вложения", "Invalid attachment number"))?,
                 }
             }
-            Err(err) => println!("Ошибка при получении ввода пользователя: {err}")
+
+            "35" => {
+                let name = match self.input_task(tr("Введите имя или номер задачи: ", "Enter task name or number: ")) {
+                    Ok(name) => name,
+                    Err(msg) => {
+                        writeln!(self.writer, "{}", msg)?;
+                        return Ok(());
+                    }
+                };
+
+                let text = match self.input(tr("Введите текст заметки: ", "Enter note text: ")) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        writeln!(self.writer, "{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err))?;
+                        return Ok(());
+                    }
+                };
+
+                match self.tasks_manager.add_note_with_hooks(&self.hooks, name.as_str(), text.as_str()) {
+                    Ok(msg) => writeln!(self.writer, "{}", msg)?,
+                    Err(msg) => writeln!(self.writer, "{}", msg)?,
+                }
+            }
+
+            _ => writeln!(self.writer, "{}", tr("Неверный ввод команды", "Invalid command"))?
         }
+
+        Ok(())
     }
 }
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    match config::Config::load(&mut args) {
+        Ok(config) => config::init(config),
+        Err(msg) => {
+            eprintln!("{}", msg);
+            std::process::exit(2);
+        }
+    }
+
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let mut manager = ConsoleTask::new();
-    manager.print_menu();
 
-    loop {
-        manager.process_command();
+    let data_file = config::current().data_file.as_deref();
+    let result = match data_file {
+        Some(filename) if Path::new(filename).exists() => manager.load_file(filename),
+        _ => Ok(())
+    };
+
+    let result = result.and_then(|_| match (config::current().sync, data_file) {
+        (config::SyncMode::Git, Some(filename)) => manager.enable_sync(filename),
+        (config::SyncMode::Git, None) => writeln!(manager.writer, "{}", tr("Для синхронизации через git укажите файл с задачами", "Specify a task file to sync through git")),
+        (config::SyncMode::None, _) => Ok(())
+    }).and_then(|_| manager.run());
+
+    if let Err(err) = result {
+        if err.kind() != std::io::ErrorKind::UnexpectedEof {
+            eprintln!("{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err));
+            std::process::exit(1);
+        }
     }
 }
\ No newline at end of file
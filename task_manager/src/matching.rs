@@ -0,0 +1,80 @@
+use crate::config::trf;
+use crate::{sort_for_display, TasksManager};
+
+const MAX_SUGGESTIONS: usize = 3;
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut name = name.chars();
+    query.chars().all(|query_char| name.any(|name_char| name_char == query_char))
+}
+
+impl TasksManager {
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        let query = name.to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let max_distance = (query.chars().count() / 3).max(2);
+        let mut candidates: Vec<(usize, &str)> = self
+            .tasks()
+            .iter()
+            .filter_map(|task| {
+                let task_name = task.name.to_lowercase();
+                let distance = edit_distance(&query, &task_name);
+                if distance <= max_distance || is_subsequence(&query, &task_name) {
+                    Some((distance, task.name.as_str()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        candidates.sort();
+        candidates.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name.to_owned()).collect()
+    }
+
+    pub fn not_found_message(&self, name: &str) -> String {
+        let message = trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name);
+        let suggestions: Vec<String> = self.suggest(name).iter().map(|name| format!("\"{}\"", name)).collect();
+
+        if suggestions.is_empty() {
+            message
+        } else {
+            trf!("{}. Возможно, вы имели в виду: {}", "{}. Did you mean: {}", message, suggestions.join(", "))
+        }
+    }
+
+    pub fn resolve_task(&self, input: &str) -> Result<usize, String> {
+        if let Some(index) = self.find_task(input) {
+            return Ok(index);
+        }
+
+        if let Ok(number) = input.parse::<usize>() {
+            let sorted = sort_for_display(self.tasks());
+            if let Some(task) = number.checked_sub(1).and_then(|index| sorted.get(index)) {
+                if let Some(index) = self.tasks().iter().position(|candidate| std::ptr::eq(candidate, *task)) {
+                    return Ok(index);
+                }
+            }
+        }
+
+        Err(self.not_found_message(input))
+    }
+}
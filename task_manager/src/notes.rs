@@ -0,0 +1,68 @@
+use std::io::Write;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use crate::config::{self, tr};
+use crate::history::HistoryEvent;
+use crate::{users, Task};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Note {
+    pub time: DateTime<Local>,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl Task {
+    pub fn add_note(&mut self, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(tr("Текст заметки не может быть пустым", "Note text cannot be empty").to_owned());
+        }
+
+        self.notes.push(Note { time: Local::now(), author: users::current_user().map(str::to_owned), text: text.to_owned() });
+        self.record(HistoryEvent::NoteAdded);
+        Ok(())
+    }
+
+    pub fn notes_contain(&self, text: &str) -> bool {
+        self.notes.iter().any(|note| note.text.to_lowercase().contains(text))
+    }
+
+    pub fn print_notes(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "{}:", tr("Заметки", "Notes"))?;
+        for note in &self.notes {
+            writeln!(
+                out,
+                "  {} {}: {}",
+                note.time.format(&config::current().date_format),
+                note.author.as_deref().unwrap_or("-"),
+                note.text
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Filter;
+    use crate::Priority;
+
+    #[test]
+    fn appends_notes_and_finds_them() {
+        let mut task = Task::new("a".to_owned(), String::new(), Priority::Low);
+        assert!(task.add_note("   ").is_err());
+        task.add_note("Позвонил заказчику").unwrap();
+        task.add_note("Ответ получен").unwrap();
+
+        let texts: Vec<&str> = task.notes.iter().map(|note| note.text.as_str()).collect();
+        assert_eq!(texts, ["Позвонил заказчику", "Ответ получен"]);
+        assert!(matches!(task.history.last().unwrap().event, HistoryEvent::NoteAdded));
+
+        assert!(Filter::parse("заказчику").unwrap().matches(&task));
+        assert!(Filter::parse("note:ответ").unwrap().matches(&task));
+        assert!(!Filter::parse("note:a").unwrap().matches(&task));
+    }
+}
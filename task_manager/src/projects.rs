@@ -0,0 +1,161 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::{tr, trf};
+use crate::{sort_for_display, table, Task, TasksManager};
+
+pub const DEFAULT_PROJECT: &str = "Основной";
+
+#[derive(Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub archive: Vec<Task>,
+}
+
+impl Project {
+    pub fn new(name: String) -> Self {
+        Self { name, tasks: vec![], archive: vec![] }
+    }
+
+    pub fn numbered_tasks(&self) -> Vec<(usize, &str, &Task)> {
+        sort_for_display(&self.tasks)
+            .into_iter()
+            .enumerate()
+            .map(|(index, task)| (index + 1, self.name.as_str(), task))
+            .collect()
+    }
+}
+
+fn legacy_id(task: &Value) -> String {
+    let mut hasher = DefaultHasher::new();
+    task["name"].to_string().hash(&mut hasher);
+    task["add_time"].to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn assign_legacy_ids(data: &mut Value) {
+    let mut lists = vec![];
+    match data {
+        Value::Array(_) => lists.push(data),
+        Value::Object(object) => {
+            if let Some(Value::Array(projects)) = object.get_mut("projects") {
+                for project in projects {
+                    if let Value::Object(project) = project {
+                        lists.extend(project.values_mut().filter(|value| value.is_array()));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for list in lists {
+        for task in list.as_array_mut().into_iter().flatten() {
+            if task.is_object() && task.get("id").is_none() {
+                task["id"] = legacy_id(task).into();
+            }
+        }
+    }
+}
+
+impl TasksManager {
+    pub fn from_json(mut data: Value) -> serde_json::Result<Self> {
+        assign_legacy_ids(&mut data);
+
+        if data.is_array() {
+            let mut manager = Self::new();
+            manager.projects[0].tasks = serde_json::from_value(data)?;
+            return Ok(manager);
+        }
+
+        let mut manager: Self = serde_json::from_value(data)?;
+        if manager.projects.is_empty() {
+            manager.projects.push(Project::new(DEFAULT_PROJECT.to_owned()));
+        }
+        Ok(manager)
+    }
+
+    pub fn current_project(&self) -> &str {
+        &self.projects[self.current].name
+    }
+
+    fn find_project(&self, name: &str) -> Option<usize> {
+        self.projects.iter().position(|project| project.name == name)
+    }
+
+    pub fn create_project(&mut self, name: &str) -> Result<String, String> {
+        if name.is_empty() {
+            return Err(tr("Имя проекта не может быть пустым", "Project name cannot be empty").to_owned());
+        }
+
+        if self.find_project(name).is_some() {
+            return Err(trf!("Проект \"{}\" уже существует", "Project \"{}\" already exists", name));
+        }
+
+        self.projects.push(Project::new(name.to_owned()));
+        Ok(trf!("Проект \"{}\" создан", "Project \"{}\" created", name))
+    }
+
+    pub fn switch_project(&mut self, name: &str) -> Result<String, String> {
+        match self.find_project(name) {
+            Some(index) => {
+                self.current = index;
+                Ok(trf!("Текущий проект: \"{}\"", "Current project: \"{}\"", name))
+            }
+            None => Err(trf!("Проекта с именем \"{}\" не существует", "Project \"{}\" does not exist", name)),
+        }
+    }
+
+    pub fn move_task(&mut self, name: &str, project: &str) -> Result<String, String> {
+        let target = match self.find_project(project) {
+            Some(target) => target,
+            None => return Err(trf!("Проекта с именем \"{}\" не существует", "Project \"{}\" does not exist", project)),
+        };
+
+        if target == self.current {
+            return Err(trf!("Задача \"{}\" уже находится в проекте \"{}\"", "Task \"{}\" is already in project \"{}\"", name, project));
+        }
+
+        if self.projects[target].tasks.iter().any(|task| task.name == name) {
+            return Err(trf!("В проекте \"{}\" уже есть задача с именем \"{}\"", "Project \"{}\" already has a task named \"{}\"", project, name));
+        }
+
+        match self.find_task(name) {
+            Some(index) => {
+                let task = self.tasks_mut().remove(index);
+                self.projects[target].tasks.push(task);
+                Ok(trf!("Задача \"{}\" перемещена в проект \"{}\"", "Task \"{}\" moved to project \"{}\"", name, project))
+            }
+            None => Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        }
+    }
+
+    pub fn print_projects(&self, out: &mut impl Write) -> std::io::Result<()> {
+        for (index, project) in self.projects.iter().enumerate() {
+            let marker = if index == self.current { tr(" (текущий)", " (current)") } else { "" };
+            writeln!(
+                out,
+                "{}",
+                trf!(
+                    "{} - задач: {}, в архиве: {}{}",
+                    "{} - tasks: {}, archived: {}{}",
+                    project.name,
+                    project.tasks.len(),
+                    project.archive.len(),
+                    marker
+                )
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn print_all_tasks(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let tasks: Vec<(usize, &str, &Task)> = self.projects.iter().flat_map(Project::numbered_tasks).collect();
+        table::print_table(out, &tasks, true)
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use crate::config::{self, tr, trf};
+use crate::{reminders, Priority, Task};
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err(tr("Не найдена закрывающая кавычка в описании", "Closing quote of the description is missing").to_owned()),
+                }
+            }
+            tokens.push(Token { text, quoted: true });
+        } else {
+            let mut text = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                text.push(c);
+                chars.next();
+            }
+            tokens.push(Token { text, quoted: false });
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn end_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    date.and_hms_opt(23, 59, 0)?.and_local_timezone(Local).earliest()
+}
+
+fn parse_due(value: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let today = now.date_naive();
+    let relative = |count: &str, unit: i64| {
+        Some(count)
+            .filter(|count| !count.is_empty() && count.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|count| count.parse::<i64>().ok())
+            .and_then(|count| count.checked_mul(unit))
+            .and_then(Duration::try_days)
+            .and_then(|offset| today.checked_add_signed(offset))
+            .and_then(end_of_day)
+    };
+
+    let due = match value.to_lowercase().as_str() {
+        "today" | "сегодня" => end_of_day(today),
+        "tomorrow" | "завтра" => end_of_day(today + Duration::days(1)),
+        value if value.starts_with('+') && value.ends_with('d') => relative(&value[1..value.len() - 1], 1),
+        value if value.starts_with('+') && value.ends_with('w') => relative(&value[1..value.len() - 1], 7),
+        _ => return reminders::parse_due_date(&value.replace('@', " ")),
+    };
+
+    due.ok_or_else(|| trf!("Неправильный срок \"{}\", ожидается today, tomorrow, +Nd, +Nw или дд-мм-гггг[@чч:мм]", "Invalid due date \"{}\", expected today, tomorrow, +Nd, +Nw or dd-mm-yyyy[@hh:mm]", value))
+}
+
+pub fn parse_quick_add(line: &str, now: DateTime<Local>) -> Result<Task, String> {
+    let mut name = vec![];
+    let mut description = None;
+    let mut priority = None;
+    let mut tags: Vec<String> = vec![];
+    let mut due_date = None;
+
+    for token in tokenize(line)? {
+        let text = token.text;
+
+        if token.quoted {
+            if description.is_some() {
+                return Err(tr("Описание задачи указано несколько раз", "Task description is given more than once").to_owned());
+            }
+            description = Some(text);
+        } else if let Some(value) = text.strip_prefix('!') {
+            if priority.is_some() {
+                return Err(tr("Приоритет задачи указан несколько раз", "Task priority is given more than once").to_owned());
+            }
+            priority = Some(Priority::from_name(value).ok_or_else(|| trf!("Неизвестный приоритет \"{}\"", "Unknown priority \"{}\"", text))?);
+        } else if let Some(tag) = text.strip_prefix('#') {
+            if tag.is_empty() {
+                return Err(tr("Пустой тег \"#\"", "Empty tag \"#\"").to_owned());
+            }
+            if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_owned());
+            }
+        } else if let Some(value) = text.strip_prefix("due:") {
+            if due_date.is_some() {
+                return Err(tr("Срок выполнения указан несколько раз", "Due date is given more than once").to_owned());
+            }
+            if value.is_empty() {
+                return Err(tr("Не указан срок после \"due:\"", "No due date after \"due:\"").to_owned());
+            }
+            due_date = Some(parse_due(value, now)?);
+        } else {
+            name.push(text);
+        }
+    }
+
+    if name.is_empty() {
+        return Err(tr("Не указано имя задачи", "Task name is not specified").to_owned());
+    }
+
+    let mut task = Task::new(
+        name.join(" "),
+        description.unwrap_or_default(),
+        priority.unwrap_or_else(|| config::current().default_priority.clone()),
+    );
+    task.tags = tags;
+    task.due_date = due_date;
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 15, 10, 30, 0).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 23, 59, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_full_line() {
+        let task = parse_quick_add("Buy milk !high #home due:tomorrow \"optional description\"", now()).unwrap();
+        assert_eq!(task.name, "Buy milk");
+        assert_eq!(task.description, "optional description");
+        assert!(task.priority == Priority::High);
+        assert_eq!(task.tags, vec!["home"]);
+        assert_eq!(task.due_date, Some(date(2024, 3, 16)));
+    }
+
+    #[test]
+    fn plain_name_uses_defaults() {
+        let task = parse_quick_add("  Позвонить   маме ", now()).unwrap();
+        assert_eq!(task.name, "Позвонить маме");
+        assert_eq!(task.description, "");
+        assert!(task.priority == config::current().default_priority);
+        assert!(task.tags.is_empty());
+        assert!(task.due_date.is_none());
+    }
+
+    #[test]
+    fn tokens_may_appear_anywhere() {
+        let task = parse_quick_add("#work \"отчёт за март\" Написать !2 отчёт #work #Urgent", now()).unwrap();
+        assert_eq!(task.name, "Написать отчёт");
+        assert_eq!(task.description, "отчёт за март");
+        assert!(task.priority == Priority::Medium);
+        assert_eq!(task.tags, vec!["work", "Urgent"]);
+    }
+
+    #[test]
+    fn parses_due_dates() {
+        let due = |line: &str| parse_quick_add(line, now()).unwrap().due_date;
+        assert_eq!(due("a due:today"), Some(date(2024, 3, 15)));
+        assert_eq!(due("a due:завтра"), Some(date(2024, 3, 16)));
+        assert_eq!(due("a due:+3d"), Some(date(2024, 3, 18)));
+        assert_eq!(due("a due:+2w"), Some(date(2024, 3, 29)));
+        assert_eq!(due("a due:01-04-2024"), Some(date(2024, 4, 1)));
+        assert_eq!(due("a due:01-04-2024@09:15"), Some(Local.with_ymd_and_hms(2024, 4, 1, 9, 15, 0).unwrap()));
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let error = |line: &str| parse_quick_add(line, now()).err().unwrap();
+        assert_eq!(error("a !urgent"), "Неизвестный приоритет \"!urgent\"");
+        assert_eq!(error("a !high !low"), "Приоритет задачи указан несколько раз");
+        assert_eq!(error("a #"), "Пустой тег \"#\"");
+        assert_eq!(error("a due:"), "Не указан срок после \"due:\"");
+        assert!(error("a due:someday").starts_with("Неправильная дата \"someday\""));
+        assert!(error("a due:+xd").starts_with("Неправильный срок \"+xd\""));
+        assert!(error("a due:+99999999999d").starts_with("Неправильный срок \"+99999999999d\""));
+        assert!(error("a due:+9223372036854775807w").starts_with("Неправильный срок"));
+        assert!(error("a due:+-3d").starts_with("Неправильный срок \"+-3d\""));
+        assert!(error("a due:++3w").starts_with("Неправильный срок \"++3w\""));
+        assert_eq!(error("a \"one\" \"two\""), "Описание задачи указано несколько раз");
+        assert_eq!(error("a \"unterminated"), "Не найдена закрывающая кавычка в описании");
+        assert_eq!(error("!high #home \"описание\""), "Не указано имя задачи");
+    }
+}
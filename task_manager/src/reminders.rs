@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::Command;
+use std::thread;
+use std::time::Duration as StdDuration;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
+use crate::config::{self, tr, trf};
+use crate::{crypto, Task, TasksManager};
+
+const DEFAULT_LEAD_MINUTES: u32 = 60;
+const DEFAULT_INTERVAL_SECONDS: u64 = 60;
+
+pub fn parse_due_date(input: &str) -> Result<DateTime<Local>, String> {
+    let naive = NaiveDateTime::parse_from_str(input, "%d-%m-%Y %H:%M").or_else(|_| {
+        NaiveDate::parse_from_str(input, "%d-%m-%Y").map(|date| date.and_hms_opt(23, 59, 0).unwrap_or_default())
+    });
+
+    match naive.ok().and_then(|naive| naive.and_local_timezone(Local).earliest()) {
+        Some(date) => Ok(date),
+        None => Err(trf!("Неправильная дата \"{}\", ожидается формат дд-мм-гггг чч:мм", "Invalid date \"{}\", expected format dd-mm-yyyy hh:mm", input)),
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub enum NotificationKind {
+    Upcoming,
+    Overdue,
+}
+
+pub struct Notification {
+    project: String,
+    task: String,
+    due_date: DateTime<Local>,
+    kind: NotificationKind,
+}
+
+impl Notification {
+    fn message(&self) -> String {
+        let due = self.due_date.format(&config::current().date_format);
+        match self.kind {
+            NotificationKind::Upcoming => {
+                trf!("Скоро срок задачи \"{}\" (проект \"{}\"): {}", "Task \"{}\" (project \"{}\") is due soon: {}", self.task, self.project, due)
+            }
+            NotificationKind::Overdue => {
+                trf!("Задача \"{}\" (проект \"{}\") просрочена, срок был {}", "Task \"{}\" (project \"{}\") is overdue, it was due {}", self.task, self.project, due)
+            }
+        }
+    }
+
+    fn key(&self) -> (String, String, DateTime<Local>, NotificationKind) {
+        (self.project.clone(), self.task.clone(), self.due_date, self.kind)
+    }
+}
+
+pub trait NotificationSink {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String>;
+}
+
+pub struct StdoutSink;
+
+impl NotificationSink for StdoutSink {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String> {
+        println!("[{}] {}", Local::now().format(&config::current().date_format), notification.message());
+        Ok(())
+    }
+}
+
+pub struct FileSink {
+    path: String,
+}
+
+impl NotificationSink for FileSink {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| trf!("Ошибка при открытии файла \"{}\": {}", "Error opening file \"{}\": {}", self.path, err))?;
+
+        writeln!(file, "[{}] {}", Local::now().format(&config::current().date_format), notification.message())
+            .map_err(|err| trf!("Ошибка при записи в файл \"{}\": {}", "Error writing file \"{}\": {}", self.path, err))
+    }
+}
+
+pub struct CommandSink {
+    command: String,
+}
+
+impl NotificationSink for CommandSink {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+
+        let kind = match notification.kind {
+            NotificationKind::Upcoming => "upcoming",
+            NotificationKind::Overdue => "overdue",
+        };
+
+        let status = command
+            .arg(&self.command)
+            .env("TASK_NAME", &notification.task)
+            .env("TASK_PROJECT", &notification.project)
+            .env("TASK_DUE", notification.due_date.to_rfc3339())
+            .env("TASK_NOTIFICATION", kind)
+            .env("TASK_MESSAGE", notification.message())
+            .status()
+            .map_err(|err| trf!("Ошибка при запуске команды \"{}\": {}", "Error running command \"{}\": {}", self.command, err))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(trf!("Команда \"{}\" завершилась с ошибкой: {}", "Command \"{}\" failed: {}", self.command, status))
+        }
+    }
+}
+
+pub struct WatchOptions {
+    file: String,
+    interval: u64,
+    lead_minutes: u32,
+    sink: Box<dyn NotificationSink>,
+    once: bool,
+}
+
+impl WatchOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut file = None;
+        let mut interval = DEFAULT_INTERVAL_SECONDS;
+        let mut lead_minutes = DEFAULT_LEAD_MINUTES;
+        let mut sink: Box<dyn NotificationSink> = Box::new(StdoutSink);
+        let mut once = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--interval" => {
+                    interval = match args.next().map(|value| value.parse()) {
+                        Some(Ok(interval)) if interval > 0 => interval,
+                        _ => return Err(tr("Неправильный интервал проверки", "Invalid check interval").to_owned()),
+                    }
+                }
+                "--lead" => {
+                    lead_minutes = match args.next().map(|value| value.parse()) {
+                        Some(Ok(lead_minutes)) => lead_minutes,
+                        _ => return Err(tr("Неправильное время напоминания", "Invalid reminder time").to_owned()),
+                    }
+                }
+                "--sink" => {
+                    sink = match args.next().map(String::as_str) {
+                        Some("stdout") => Box::new(StdoutSink),
+                        Some(value) if value.starts_with("file:") => {
+                            Box::new(FileSink { path: value["file:".len()..].to_owned() })
+                        }
+                        Some(value) if value.starts_with("command:") => {
+                            Box::new(CommandSink { command: value["command:".len()..].to_owned() })
+                        }
+                        _ => return Err(tr("Неправильный способ уведомления, ожидается stdout, file:<путь> или command:<команда>", "Invalid notification sink, expected stdout, file:<path> or command:<command>").to_owned()),
+                    }
+                }
+                "--once" => once = true,
+                _ if file.is_none() && !arg.starts_with("--") => file = Some(arg.clone()),
+                _ => return Err(trf!("Неизвестный аргумент \"{}\"", "Unknown argument \"{}\"", arg)),
+            }
+        }
+
+        match file.or_else(|| config::current().data_file.clone()) {
+            Some(file) => Ok(Self { file, interval, lead_minutes, sink, once }),
+            None => Err(tr("Не указан файл с задачами", "Task file is not specified").to_owned()),
+        }
+    }
+}
+
+impl Task {
+    fn notification_kind(&self, now: DateTime<Local>, default_lead_minutes: u32) -> Option<NotificationKind> {
+        let due_date = self.due_date?;
+        if self.is_closed() {
+            return None;
+        }
+
+        let lead = Duration::minutes(self.reminder_lead_minutes.unwrap_or(default_lead_minutes) as i64);
+        if now >= due_date {
+            Some(NotificationKind::Overdue)
+        } else if now >= due_date - lead {
+            Some(NotificationKind::Upcoming)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_overdue(&self) -> bool {
+        !self.is_closed() && self.due_date.is_some_and(|due_date| Local::now() >= due_date)
+    }
+}
+
+impl TasksManager {
+    pub fn set_due_date(
+        &mut self,
+        name: &str,
+        due_date: Option<DateTime<Local>>,
+        reminder_lead_minutes: Option<u32>,
+    ) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        let task = &mut self.tasks_mut()[index];
+        let format_due = |due_date: Option<DateTime<Local>>| match due_date {
+            Some(due_date) => due_date.format(&config::current().date_format).to_string(),
+            None => tr("нет", "none").to_owned(),
+        };
+        task.record_edit("Срок выполнения", format_due(task.due_date), format_due(due_date));
+        task.due_date = due_date;
+        task.reminder_lead_minutes = reminder_lead_minutes;
+
+        match due_date {
+            Some(_) => Ok(trf!("Срок выполнения задачи \"{}\" установлен", "Due date of task \"{}\" set", name)),
+            None => Ok(trf!("Срок выполнения задачи \"{}\" удалён", "Due date of task \"{}\" removed", name)),
+        }
+    }
+
+    pub fn due_notifications(&self, now: DateTime<Local>, default_lead_minutes: u32) -> Vec<Notification> {
+        let mut notifications = vec![];
+
+        for project in &self.projects {
+            for task in &project.tasks {
+                if let (Some(kind), Some(due_date)) = (task.notification_kind(now, default_lead_minutes), task.due_date) {
+                    notifications.push(Notification {
+                        project: project.name.clone(),
+                        task: task.name.clone(),
+                        due_date,
+                        kind,
+                    });
+                }
+            }
+        }
+
+        notifications
+    }
+}
+
+pub fn watch(mut options: WatchOptions) {
+    let mut notified = HashSet::new();
+
+    let passphrase = if crypto::is_encrypted_file(&options.file) {
+        match crate::prompt_password(tr("Файл зашифрован, введите пароль: ", "File is encrypted, enter password: ")) {
+            Ok(passphrase) => Some(passphrase),
+            Err(err) => {
+                eprintln!("{}", trf!("Ошибка при получении ввода пользователя: {}", "Error reading user input: {}", err));
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    println!("{}", trf!("Отслеживание сроков задач в файле \"{}\" запущено", "Watching task due dates in file \"{}\"", options.file));
+
+    loop {
+        let mut manager = TasksManager::new();
+        match manager.read_from_file(&options.file, passphrase.as_deref()) {
+            Ok(_) => {
+                for notification in manager.due_notifications(Local::now(), options.lead_minutes) {
+                    if notified.contains(&notification.key()) {
+                        continue;
+                    }
+
+                    match options.sink.notify(&notification) {
+                        Ok(_) => {
+                            notified.insert(notification.key());
+                        }
+                        Err(msg) => eprintln!("{}", msg),
+                    }
+                }
+            }
+            Err(msg) => eprintln!("{}", msg),
+        }
+
+        if options.once {
+            break;
+        }
+
+        thread::sleep(StdDuration::from_secs(options.interval));
+    }
+}
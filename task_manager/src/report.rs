@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, Duration};
+use serde::Serialize;
+use crate::config::{self, tr, trf};
+use crate::{Priority, Task, TasksManager};
+
+#[derive(Serialize)]
+pub struct PriorityStats {
+    priority: Priority,
+    total: usize,
+    completed: usize,
+}
+
+#[derive(Serialize)]
+pub struct StatusStats {
+    status: String,
+    total: usize,
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    total: usize,
+    completed: usize,
+    not_completed: usize,
+    by_status: Vec<StatusStats>,
+    by_priority: Vec<PriorityStats>,
+    completion_rate: f64,
+    average_completion_seconds: Option<i64>,
+    completed_per_day: BTreeMap<String, usize>,
+    completed_per_week: BTreeMap<String, usize>,
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    let days = minutes / (24 * 60);
+    let hours = minutes % (24 * 60) / 60;
+    let minutes = minutes % 60;
+
+    if days > 0 {
+        trf!("{} д {} ч {} мин", "{} d {} h {} min", days, hours, minutes)
+    } else if hours > 0 {
+        trf!("{} ч {} мин", "{} h {} min", hours, minutes)
+    } else {
+        trf!("{} мин", "{} min", minutes)
+    }
+}
+
+impl TasksManager {
+    pub fn report(&self) -> Report {
+        let tasks: Vec<&Task> = self.tasks().iter().chain(self.archive()).collect();
+        let total = tasks.len();
+        let completed = tasks.iter().filter(|task| task.is_completed()).count();
+
+        let mut statuses = config::current().workflow.states.clone();
+        for task in &tasks {
+            if !statuses.contains(&task.status) {
+                statuses.push(task.status.clone());
+            }
+        }
+        let by_status = statuses
+            .into_iter()
+            .map(|status| StatusStats { total: tasks.iter().filter(|task| task.status == status).count(), status })
+            .collect();
+
+        let by_priority = [Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .map(|priority| {
+                let tasks = tasks.iter().filter(|task| task.priority == priority);
+                PriorityStats {
+                    total: tasks.clone().count(),
+                    completed: tasks.filter(|task| task.is_completed()).count(),
+                    priority,
+                }
+            })
+            .collect();
+
+        let completion_rate = if total == 0 {
+            0.0
+        } else {
+            completed as f64 * 100.0 / total as f64
+        };
+
+        let mut completion_durations = vec![];
+        let mut completed_per_day = BTreeMap::new();
+        let mut completed_per_week = BTreeMap::new();
+
+        for task in &tasks {
+            if let (true, Some(completed_time)) = (task.is_completed(), task.completed_time) {
+                completion_durations.push((completed_time - task.add_time).num_seconds());
+
+                let day = completed_time.format("%Y-%m-%d").to_string();
+                *completed_per_day.entry(day).or_insert(0) += 1;
+
+                let week = completed_time.iso_week();
+                let week = format!("{}-W{:02}", week.year(), week.week());
+                *completed_per_week.entry(week).or_insert(0) += 1;
+            }
+        }
+
+        let average_completion_seconds = if completion_durations.is_empty() {
+            None
+        } else {
+            Some(completion_durations.iter().sum::<i64>() / completion_durations.len() as i64)
+        };
+
+        Report {
+            total,
+            completed,
+            not_completed: total - completed,
+            by_status,
+            by_priority,
+            completion_rate,
+            average_completion_seconds,
+            completed_per_day,
+            completed_per_week,
+        }
+    }
+}
+
+impl Report {
+    pub fn to_text(&self) -> String {
+        let mut text = trf!(
+            "Всего задач: {}\nВыполнено: {}\nНе выполнено: {}\nПроцент выполнения: {:.1}%\n",
+            "Total tasks: {}\nCompleted: {}\nNot completed: {}\nCompletion rate: {:.1}%\n",
+            self.total, self.completed, self.not_completed, self.completion_rate
+        );
+
+        text.push_str(tr("\nПо статусам:\n", "\nBy status:\n"));
+        for stats in &self.by_status {
+            text.push_str(&format!("  {}: {}\n", stats.status, stats.total));
+        }
+
+        text.push_str(tr("\nПо приоритетам:\n", "\nBy priority:\n"));
+        for stats in &self.by_priority {
+            text.push_str(&trf!(
+                "  {}: всего {}, выполнено {}\n",
+                "  {}: total {}, completed {}\n",
+                stats.priority,
+                stats.total,
+                stats.completed
+            ));
+        }
+
+        match self.average_completion_seconds {
+            Some(seconds) => text.push_str(&trf!(
+                "\nСреднее время выполнения: {}\n",
+                "\nAverage completion time: {}\n",
+                format_duration(Duration::seconds(seconds))
+            )),
+            None => text.push_str(tr("\nСреднее время выполнения: нет данных\n", "\nAverage completion time: no data\n")),
+        }
+
+        text.push_str(tr("\nВыполнено по дням:\n", "\nCompleted by day:\n"));
+        if self.completed_per_day.is_empty() {
+            text.push_str(tr("  нет данных\n", "  no data\n"));
+        }
+        for (day, count) in &self.completed_per_day {
+            text.push_str(&format!("  {}: {}\n", day, count));
+        }
+
+        text.push_str(tr("\nВыполнено по неделям:\n", "\nCompleted by week:\n"));
+        if self.completed_per_week.is_empty() {
+            text.push_str(tr("  нет данных\n", "  no data\n"));
+        }
+        for (week, count) in &self.completed_per_week {
+            text.push_str(&format!("  {}: {}\n", week, count));
+        }
+
+        text
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| trf!("Ошибка при формировании отчёта: {}", "Error building report: {}", err))
+    }
+}
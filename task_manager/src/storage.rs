@@ -0,0 +1,257 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+use crate::config::{self, tr, trf, StorageMode};
+use crate::{backup, crypto, journal, Project, Task, TasksManager};
+
+pub struct FileState {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    hash: u64,
+    journal: bool,
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn canonical_path(filename: &str) -> PathBuf {
+    fs::canonicalize(filename).unwrap_or_else(|_| PathBuf::from(filename))
+}
+
+impl FileState {
+    fn capture(filename: &str, file: &File, data: &[u8]) -> Self {
+        Self {
+            path: canonical_path(filename),
+            modified: file.metadata().and_then(|metadata| metadata.modified()).ok(),
+            hash: content_hash(data),
+            journal: journal::is_journal(data),
+        }
+    }
+
+    fn is_for(&self, filename: &str) -> bool {
+        self.path == canonical_path(filename)
+    }
+
+    fn matches(&self, file: &File, data: &[u8]) -> bool {
+        let modified = file.metadata().and_then(|metadata| metadata.modified()).ok();
+        (modified.is_some() && modified == self.modified) || content_hash(data) == self.hash
+    }
+}
+
+pub fn read_locked(filename: &str) -> Result<(Vec<u8>, FileState), String> {
+    let mut file = File::open(filename).map_err(|err| trf!("Ошибка при открытии файла {}", "Error opening file {}", err))?;
+    file.lock_shared().map_err(|err| trf!("Ошибка при блокировке файла: {}", "Error locking file: {}", err))?;
+
+    let mut data = vec![];
+    file.read_to_end(&mut data).map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err))?;
+
+    let state = FileState::capture(filename, &file, &data);
+    Ok((data, state))
+}
+
+fn open_locked(filename: &str, expected: Option<&FileState>) -> Result<(File, Vec<u8>), String> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(filename)
+        .map_err(|err| trf!("Ошибка при создании файла: {}", "Error creating file: {}", err))?;
+    file.lock().map_err(|err| trf!("Ошибка при блокировке файла: {}", "Error locking file: {}", err))?;
+
+    let mut current = vec![];
+    file.read_to_end(&mut current).map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err))?;
+    if let Some(expected) = expected {
+        if !current.is_empty() && !expected.matches(&file, &current) {
+            return Err(trf!("Файл \"{}\" был изменён другим процессом после последней загрузки", "File \"{}\" was modified by another process since it was last loaded", filename));
+        }
+    }
+
+    Ok((file, current))
+}
+
+fn write_at(filename: &str, file: &mut File, offset: usize, data: Vec<u8>, mut content: Vec<u8>) -> Result<FileState, String> {
+    file.set_len(offset as u64)
+        .and_then(|_| file.seek(SeekFrom::Start(offset as u64)))
+        .and_then(|_| file.write_all(&data))
+        .and_then(|_| file.sync_all())
+        .map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+
+    content.truncate(offset);
+    content.extend(data);
+    Ok(FileState::capture(filename, file, &content))
+}
+
+pub fn write_locked(filename: &str, data: Vec<u8>, expected: Option<&FileState>) -> Result<FileState, String> {
+    let (mut file, _) = open_locked(filename, expected)?;
+    write_at(filename, &mut file, 0, data, vec![])
+}
+
+fn append_journal(filename: &str, state: &serde_json::Value, expected: Option<&FileState>) -> Result<FileState, String> {
+    let (mut file, current) = open_locked(filename, expected)?;
+
+    if !journal::is_journal(&current) {
+        return write_at(filename, &mut file, 0, journal::snapshot(state)?, vec![]);
+    }
+
+    let old = journal::replay(&current)?;
+    let mut data = journal::changes(&old.state, state)?;
+    if old.valid_len > 0 && current[old.valid_len - 1] != b'\n' {
+        data.insert(0, b'\n');
+    }
+    write_at(filename, &mut file, old.valid_len, data, current)
+}
+
+impl Task {
+    fn is_newer_than(&self, other: &Task) -> bool {
+        self.last_modified() > other.last_modified()
+    }
+}
+
+fn merge_tasks(local: &mut Vec<Task>, local_other: &[Task], incoming: Vec<Task>) -> usize {
+    let mut changed = 0;
+
+    for task in incoming {
+        match local.iter().position(|local_task| local_task.name == task.name) {
+            Some(index) => {
+                if task.is_newer_than(&local[index]) {
+                    local[index] = task;
+                    changed += 1;
+                }
+            }
+            None if local_other.iter().any(|local_task| local_task.name == task.name) => {}
+            None => {
+                local.push(task);
+                changed += 1;
+            }
+        }
+    }
+
+    changed
+}
+
+impl TasksManager {
+    pub fn serialize(&self, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+        let data = serde_json::to_vec(self).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+
+        match passphrase {
+            Some(passphrase) => crypto::encrypt(&data, passphrase),
+            None => Ok(data),
+        }
+    }
+
+    pub fn deserialize(data: Vec<u8>, filename: &str, passphrase: Option<&str>) -> Result<Self, String> {
+        if journal::is_journal(&data) {
+            let journal = journal::replay(&data).map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err))?;
+            return Self::from_json(journal.state).map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err));
+        }
+
+        let data = if crypto::is_encrypted(&data) {
+            match passphrase {
+                Some(passphrase) => crypto::decrypt(&data, passphrase)?,
+                None => return Err(trf!("Файл \"{}\" зашифрован, требуется пароль", "File \"{}\" is encrypted, a password is required", filename)),
+            }
+        } else {
+            data
+        };
+
+        let data: serde_json::Value = serde_json::from_slice(&data)
+            .map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err))?;
+
+        Self::from_json(data).map_err(|err| trf!("Ошибка при чтении файла: {}", "Error reading file: {}", err))
+    }
+
+    pub fn load_file(filename: &str, passphrase: Option<&str>) -> Result<(Self, FileState), String> {
+        let (data, state) = read_locked(filename)?;
+        Ok((Self::deserialize(data, filename, passphrase)?, state))
+    }
+
+    pub fn is_source_file(&self, filename: &str) -> bool {
+        self.file_state.as_ref().is_some_and(|state| state.is_for(filename))
+    }
+
+    pub fn is_modified_externally(&self, filename: &str) -> bool {
+        let state = match &self.file_state {
+            Some(state) if state.is_for(filename) => state,
+            _ => return false,
+        };
+
+        match File::open(filename) {
+            Ok(mut file) => {
+                let mut data = vec![];
+                file.read_to_end(&mut data).is_ok() && !state.matches(&file, &data)
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn uses_journal(&self, filename: &str) -> bool {
+        config::current().storage == StorageMode::Journal
+            || self.file_state.as_ref().is_some_and(|state| state.is_for(filename) && state.journal)
+    }
+
+    pub fn write_file(&mut self, filename: &str, passphrase: Option<&str>, check: bool) -> Result<String, String> {
+        let expected = self.file_state.as_ref().filter(|state| check && state.is_for(filename));
+        backup::create(filename)?;
+
+        let state = if self.uses_journal(filename) {
+            if passphrase.is_some() {
+                return Err(tr("Журнал изменений не поддерживает шифрование, используйте storage = \"json\"", "The change journal does not support encryption, use storage = \"json\"").to_owned());
+            }
+            let value = serde_json::to_value(&*self).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+            append_journal(filename, &value, expected)?
+        } else {
+            write_locked(filename, self.serialize(passphrase)?, expected)?
+        };
+
+        self.file_state = Some(state);
+        Ok(tr("Данные успешно сохранены", "Data saved").to_owned())
+    }
+
+    pub fn compact_file(&mut self, filename: &str) -> Result<String, String> {
+        let events = match fs::read(filename) {
+            Ok(data) if journal::is_journal(&data) => journal::replay(&data)?.events,
+            Ok(data) if !data.is_empty() && !self.is_source_file(filename) => {
+                return Err(trf!("Файл \"{}\" уже существует", "File \"{}\" already exists", filename));
+            }
+            _ => 0,
+        };
+
+        let value = serde_json::to_value(&*self).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))?;
+        let expected = self.file_state.as_ref().filter(|state| state.is_for(filename));
+        backup::create(filename)?;
+        self.file_state = Some(write_locked(filename, journal::snapshot(&value)?, expected)?);
+        Ok(trf!("Журнал сжат, событий заменено снимком: {}", "Journal compacted, events replaced with a snapshot: {}", events))
+    }
+
+    pub fn merge_from_file(&mut self, filename: &str, passphrase: Option<&str>) -> Result<String, String> {
+        let (other, state) = Self::load_file(filename, passphrase)?;
+        let mut changed = 0;
+
+        for project in other.projects {
+            let index = match self.projects.iter().position(|local| local.name == project.name) {
+                Some(index) => index,
+                None => {
+                    self.projects.push(Project::new(project.name.clone()));
+                    self.projects.len() - 1
+                }
+            };
+
+            let local = &mut self.projects[index];
+            changed += merge_tasks(&mut local.tasks, &local.archive, project.tasks);
+            changed += merge_tasks(&mut local.archive, &local.tasks, project.archive);
+        }
+
+        if self.file_state.as_ref().is_some_and(|local| local.is_for(filename)) {
+            self.file_state = Some(state);
+        }
+
+        Ok(trf!("Изменения из файла \"{}\" объединены, обновлено задач: {}", "Changes from file \"{}\" merged, tasks updated: {}", filename, changed))
+    }
+}
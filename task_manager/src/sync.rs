@@ -0,0 +1,599 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde_json::{Map, Value};
+use crate::config::{tr, trf};
+use crate::{crypto, TasksManager};
+
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+pub struct Conflict {
+    pub name: String,
+    pub fields: Vec<String>,
+    index: usize,
+    ours: Option<Value>,
+    theirs: Option<Value>,
+}
+
+impl Conflict {
+    pub fn describe(&self) -> String {
+        let mut text = trf!("Конфликт в задаче \"{}\":", "Conflict in task \"{}\":", self.name);
+        match (&self.ours, &self.theirs) {
+            (Some(ours), Some(theirs)) => {
+                for field in &self.fields {
+                    text.push_str(&trf!("\n  {}: у нас {}, у них {}", "\n  {}: ours {}, theirs {}", field, ours[field], theirs[field]));
+                }
+            }
+            (None, _) => text.push_str(tr("\n  у нас задача удалена, у них изменена", "\n  task removed on our side, changed on theirs")),
+            (_, None) => text.push_str(tr("\n  у нас задача изменена, у них удалена", "\n  task changed on our side, removed on theirs")),
+        }
+        text
+    }
+}
+
+pub struct Merge {
+    tasks: Vec<Option<Value>>,
+    pub conflicts: Vec<Conflict>,
+    projects: Vec<String>,
+    auto_archive_days: Value,
+    upstream: String,
+    revision: String,
+}
+
+enum Merged {
+    Value(Option<Value>),
+    Conflict { fields: Vec<String>, ours: Option<Value>, theirs: Option<Value> },
+}
+
+fn merge_value<'a>(base: Option<&'a Value>, ours: Option<&'a Value>, theirs: Option<&'a Value>) -> Option<Option<&'a Value>> {
+    if ours == theirs || base == theirs {
+        Some(ours)
+    } else if base == ours {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+fn merge_list(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>, order: &str, key: impl Fn(&Value) -> Value) -> Value {
+    let items = |value: Option<&Value>| value.and_then(Value::as_array).cloned().unwrap_or_default();
+    let base = items(base);
+    let mut merged = items(ours);
+
+    for item in items(theirs) {
+        match merged.iter().position(|existing| key(existing) == key(&item)) {
+            Some(index) if base.contains(&merged[index]) => merged[index] = item,
+            Some(_) => {}
+            None if !base.contains(&item) => merged.push(item),
+            None => {}
+        }
+    }
+
+    merged.sort_by(|a, b| a[order].as_str().cmp(&b[order].as_str()));
+    Value::Array(merged)
+}
+
+fn merge_task(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Merged {
+    if let Some(value) = merge_value(base, ours, theirs) {
+        return Merged::Value(value.cloned());
+    }
+
+    let (ours, theirs) = match (ours.and_then(Value::as_object), theirs.and_then(Value::as_object)) {
+        (Some(ours), Some(theirs)) => (ours, theirs),
+        _ => return Merged::Conflict { fields: vec![], ours: ours.cloned(), theirs: theirs.cloned() },
+    };
+    let empty = Map::new();
+    let base = base.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut fields = vec![];
+    let mut merged_ours = Map::new();
+    let mut merged_theirs = Map::new();
+
+    for key in ours.keys().chain(theirs.keys().filter(|key| !ours.contains_key(*key))) {
+        let (b, o, t) = (base.get(key), ours.get(key), theirs.get(key));
+        let value = match key.as_str() {
+            "history" => Some(merge_list(b, o, t, "time", Value::clone)),
+            "notes" => Some(merge_list(b, o, t, "time", Value::clone)),
+            "time_entries" => Some(merge_list(b, o, t, "start", |entry| entry["start"].clone())),
+            _ => match merge_value(b, o, t) {
+                Some(value) => value.cloned(),
+                None => {
+                    fields.push(key.clone());
+                    merged_ours.insert(key.clone(), o.cloned().unwrap_or_default());
+                    merged_theirs.insert(key.clone(), t.cloned().unwrap_or_default());
+                    continue;
+                }
+            },
+        };
+
+        if let Some(value) = value {
+            merged_ours.insert(key.clone(), value.clone());
+            merged_theirs.insert(key.clone(), value);
+        }
+    }
+
+    if fields.is_empty() {
+        Merged::Value(Some(Value::Object(merged_ours)))
+    } else {
+        Merged::Conflict { fields, ours: Some(Value::Object(merged_ours)), theirs: Some(Value::Object(merged_theirs)) }
+    }
+}
+
+pub fn flatten(data: &Value) -> Vec<(String, Value)> {
+    let mut tasks = vec![];
+
+    for project in data["projects"].as_array().into_iter().flatten() {
+        for (list, archived) in [("tasks", false), ("archive", true)] {
+            for task in project[list].as_array().into_iter().flatten() {
+                let mut task = task.clone();
+                task["project"] = project["name"].clone();
+                task["archived"] = archived.into();
+                tasks.push((task["id"].as_str().unwrap_or_default().to_owned(), task));
+            }
+        }
+    }
+
+    tasks
+}
+
+pub fn project_names(data: &Value) -> Vec<String> {
+    data["projects"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|project| project["name"].as_str().map(str::to_owned))
+        .collect()
+}
+
+pub fn unflatten(project_names: &[String], tasks: impl IntoIterator<Item = Value>, auto_archive_days: Value) -> Value {
+    let mut projects: Vec<Value> = project_names
+        .iter()
+        .map(|name| serde_json::json!({ "name": name, "tasks": [], "archive": [] }))
+        .collect();
+
+    for mut task in tasks {
+        let project = task.as_object_mut().and_then(|task| task.remove("project")).unwrap_or_default();
+        let archived = task.as_object_mut().and_then(|task| task.remove("archived")) == Some(Value::Bool(true));
+
+        let index = match projects.iter().position(|existing| existing["name"] == project) {
+            Some(index) => index,
+            None => {
+                projects.push(serde_json::json!({ "name": project, "tasks": [], "archive": [] }));
+                projects.len() - 1
+            }
+        };
+        if let Some(list) = projects[index][if archived { "archive" } else { "tasks" }].as_array_mut() {
+            list.push(task);
+        }
+    }
+
+    serde_json::json!({ "projects": projects, "auto_archive_days": auto_archive_days })
+}
+
+impl Merge {
+    fn new(base: &Value, ours: &Value, theirs: &Value) -> Self {
+        let base_tasks: HashMap<String, Value> = flatten(base).into_iter().collect();
+        let ours_tasks = flatten(ours);
+        let theirs_tasks = flatten(theirs);
+
+        let mut ids: Vec<&String> = ours_tasks.iter().map(|(id, _)| id).collect();
+        ids.extend(theirs_tasks.iter().map(|(id, _)| id).filter(|id| !ours_tasks.iter().any(|(ours, _)| ours == *id)));
+
+        let ours_tasks: HashMap<&String, &Value> = ours_tasks.iter().map(|(id, task)| (id, task)).collect();
+        let theirs_tasks: HashMap<&String, &Value> = theirs_tasks.iter().map(|(id, task)| (id, task)).collect();
+
+        let mut tasks = vec![];
+        let mut conflicts = vec![];
+        for id in ids {
+            let (ours, theirs) = (ours_tasks.get(id).copied(), theirs_tasks.get(id).copied());
+            match merge_task(base_tasks.get(id), ours, theirs) {
+                Merged::Value(task) => tasks.push(task),
+                Merged::Conflict { fields, ours, theirs } => {
+                    let name = ours.as_ref().or(theirs.as_ref()).and_then(|task| task["name"].as_str()).unwrap_or_default();
+                    conflicts.push(Conflict { name: name.to_owned(), fields, index: tasks.len(), ours: ours.clone(), theirs });
+                    tasks.push(ours);
+                }
+            }
+        }
+
+        let mut projects = project_names(ours);
+        for name in project_names(theirs) {
+            if !projects.contains(&name) {
+                projects.push(name);
+            }
+        }
+
+        let (b, o, t) = (&base["auto_archive_days"], &ours["auto_archive_days"], &theirs["auto_archive_days"]);
+        let auto_archive_days = merge_value(Some(b), Some(o), Some(t)).flatten().unwrap_or(o).clone();
+
+        Self { tasks, conflicts, projects, auto_archive_days, upstream: String::new(), revision: String::new() }
+    }
+
+    pub fn resolve(&mut self, conflict: usize, side: Side) {
+        let conflict = &self.conflicts[conflict];
+        self.tasks[conflict.index] = match side {
+            Side::Ours => conflict.ours.clone(),
+            Side::Theirs => conflict.theirs.clone(),
+        };
+    }
+
+    fn into_value(self) -> Value {
+        unflatten(&self.projects, self.tasks.into_iter().flatten(), self.auto_archive_days)
+    }
+}
+
+pub fn describe_changes(old: &Value, new: &Value) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let old_tasks: HashMap<String, Value> = flatten(old).into_iter().collect();
+    let new_tasks = flatten(new);
+    let mut changes = vec![];
+
+    for (id, task) in &new_tasks {
+        let name = &task["name"];
+        match old_tasks.get(id) {
+            None => changes.push(trf!("Добавлена задача {}", "Added task {}", name)),
+            Some(old) if old["project"] != task["project"] => {
+                changes.push(trf!("Задача {} перемещена в проект {}", "Task {} moved to project {}", name, task["project"]))
+            }
+            Some(old) if old["archived"] != task["archived"] => changes.push(match task["archived"].as_bool() {
+                Some(true) => trf!("Задача {} перемещена в архив", "Task {} moved to archive", name),
+                _ => trf!("Задача {} восстановлена из архива", "Task {} restored from archive", name),
+            }),
+            Some(old) if old != task => {
+                let fields: Vec<&str> = task
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(key, value)| key.as_str() != "history" && old.get(*key) != Some(*value))
+                    .map(|(key, _)| key.as_str())
+                    .collect();
+                if fields.is_empty() {
+                    changes.push(trf!("Изменена задача {}", "Changed task {}", name));
+                } else {
+                    changes.push(trf!("Изменена задача {} ({})", "Changed task {} ({})", name, fields.join(", ")));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (id, task) in &old_tasks {
+        if !new_tasks.iter().any(|(new_id, _)| new_id == id) {
+            changes.push(trf!("Удалена задача {}", "Removed task {}", task["name"]));
+        }
+    }
+
+    for name in project_names(new) {
+        if !project_names(old).contains(&name) {
+            changes.push(trf!("Создан проект \"{}\"", "Created project \"{}\"", name));
+        }
+    }
+
+    Some(match changes.len() {
+        0 => tr("Обновлены настройки задач", "Updated task settings").to_owned(),
+        1 => changes.remove(0),
+        count => trf!("Изменения задач: {}\n\n{}", "Task changes: {}\n\n{}", count, changes.join("\n")),
+    })
+}
+
+fn to_value(manager: &TasksManager) -> Result<Value, String> {
+    serde_json::to_value(manager).map_err(|err| trf!("Ошибка при сохранении данных: {}", "Error saving data: {}", err))
+}
+
+struct GitRepo {
+    dir: PathBuf,
+}
+
+impl GitRepo {
+    fn command(&self, args: &[&str]) -> Result<std::process::Output, String> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(|err| trf!("Ошибка при запуске git: {}", "Error running git: {}", err))
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, String> {
+        let output = self.command(args)?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_owned())
+        } else {
+            Err(trf!("Ошибка git {}: {}", "git {} error: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    }
+
+    fn succeeds(&self, args: &[&str]) -> Result<bool, String> {
+        Ok(self.command(args)?.status.success())
+    }
+}
+
+pub enum Pull {
+    NoUpstream,
+    UpToDate,
+    FastForward,
+    Merge(Merge),
+}
+
+pub struct GitSync {
+    repo: GitRepo,
+    filename: String,
+    path: String,
+    snapshot: Value,
+}
+
+impl GitSync {
+    pub fn open(filename: &str, manager: &TasksManager) -> Result<Self, String> {
+        if crypto::is_encrypted_file(filename) {
+            return Err(trf!("Синхронизация через git не поддерживает зашифрованный файл \"{}\"", "Git sync does not support encrypted file \"{}\"", filename));
+        }
+
+        let file = Path::new(filename);
+        let dir = match file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let repo = GitRepo { dir };
+
+        let prefix = repo
+            .run(&["rev-parse", "--show-prefix"])
+            .map_err(|_| trf!("Файл \"{}\" не находится в git-репозитории", "File \"{}\" is not in a git repository", filename))?;
+        let name = file.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+        Ok(Self { repo, filename: filename.to_owned(), path: format!("{}{}", prefix, name), snapshot: to_value(manager)? })
+    }
+
+    fn commit_file(&self, message: &str) -> Result<bool, String> {
+        self.repo.run(&["add", "--", &self.path])?;
+        if self.repo.succeeds(&["diff", "--cached", "--quiet", "--", &self.path])? {
+            return Ok(false);
+        }
+        self.repo.run(&["commit", "-q", "-m", message, "--", &self.path])?;
+        Ok(true)
+    }
+
+    pub fn commit(&mut self, manager: &mut TasksManager) -> Result<Option<String>, String> {
+        let current = to_value(manager)?;
+        let message = match describe_changes(&self.snapshot, &current) {
+            Some(message) => message,
+            None => return Ok(None),
+        };
+
+        manager.store_to_file(&self.filename, None)?;
+        self.commit_file(&message)?;
+        self.snapshot = current;
+        Ok(Some(message))
+    }
+
+    fn load_revision(&self, revision: &str) -> Result<Value, String> {
+        match self.repo.run(&["show", &format!("{}:{}", revision, self.path)]) {
+            Ok(data) => to_value(&TasksManager::deserialize(data.into_bytes(), &self.filename, None)?),
+            Err(_) => to_value(&TasksManager::new()),
+        }
+    }
+
+    pub fn pull(&mut self, manager: &mut TasksManager) -> Result<Pull, String> {
+        if !Path::new(&self.filename).exists() {
+            manager.store_to_file(&self.filename, None)?;
+        }
+        self.commit(manager)?;
+        self.commit_file(tr("Обновлён файл задач", "Updated task file"))?;
+
+        let upstream = match self.repo.run(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"]) {
+            Ok(upstream) => upstream,
+            Err(_) => return Ok(Pull::NoUpstream),
+        };
+
+        self.repo.run(&["fetch", "-q"])?;
+        let head = self.repo.run(&["rev-parse", "HEAD"])?;
+        let revision = self.repo.run(&["rev-parse", "@{u}"])?;
+        let base = self.repo.run(&["merge-base", "HEAD", "@{u}"])?;
+
+        if base == revision {
+            return Ok(Pull::UpToDate);
+        }
+
+        if base == head {
+            let project = manager.current_project().to_owned();
+            self.repo.run(&["merge", "-q", "--ff-only", "@{u}"])?;
+            manager.read_from_file(&self.filename, None)?;
+            let _ = manager.switch_project(&project);
+            self.snapshot = to_value(manager)?;
+            return Ok(Pull::FastForward);
+        }
+
+        let mut merge = Merge::new(&self.load_revision(&base)?, &self.snapshot, &self.load_revision(&revision)?);
+        merge.upstream = upstream;
+        merge.revision = revision;
+        Ok(Pull::Merge(merge))
+    }
+
+    pub fn finish(&mut self, manager: &mut TasksManager, merge: Merge) -> Result<String, String> {
+        let subject = trf!("Объединение задач с {}", "Merge tasks with {}", merge.upstream);
+        let revision = merge.revision.clone();
+        let project = manager.current_project().to_owned();
+
+        let mut merged = TasksManager::from_json(merge.into_value())
+            .map_err(|err| trf!("Ошибка при объединении задач: {}", "Error merging tasks: {}", err))?;
+        merged.file_state = manager.file_state.take();
+        let _ = merged.switch_project(&project);
+        *manager = merged;
+
+        let current = to_value(manager)?;
+        let message = match describe_changes(&self.snapshot, &current) {
+            Some(changes) => format!("{}\n\n{}", subject, changes),
+            None => subject.clone(),
+        };
+
+        self.repo.run(&["merge", "-q", "--no-ff", "--no-commit", "-s", "ours", &revision])?;
+        manager.store_to_file(&self.filename, None)?;
+        self.repo.run(&["add", "--", &self.path])?;
+        self.repo.run(&["commit", "-q", "-m", &message])?;
+        self.snapshot = current;
+        Ok(subject)
+    }
+
+    pub fn push(&self) -> Result<String, String> {
+        if self.repo.succeeds(&["rev-parse", "--abbrev-ref", "@{u}"])? {
+            self.repo.run(&["push", "-q"])?;
+        } else {
+            self.repo.run(&["push", "-q", "-u", "origin", "HEAD"])?;
+        }
+        Ok(tr("Изменения отправлены в удалённый репозиторий", "Changes pushed to the remote repository").to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::*;
+    use crate::{Priority, Task};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+            let dir = std::env::temp_dir().join(format!("task_manager_sync_{}_{}", std::process::id(), id));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn clone(root: &Path, name: &str) -> String {
+        git(root, &["clone", "-q", "remote.git", name]);
+        let dir = root.join(name);
+        git(&dir, &["config", "user.name", name]);
+        git(&dir, &["config", "user.email", &format!("{}@example.com", name)]);
+        dir.join("tasks.json").to_string_lossy().into_owned()
+    }
+
+    fn open(file: &str) -> (TasksManager, GitSync) {
+        let mut manager = TasksManager::new();
+        manager.read_from_file(file, None).unwrap();
+        let sync = GitSync::open(file, &manager).unwrap();
+        (manager, sync)
+    }
+
+    fn setup() -> (TempDir, String, String) {
+        let root = TempDir::new();
+        git(&root.0, &["init", "-q", "--bare", "remote.git"]);
+
+        let first = clone(&root.0, "first");
+        let mut manager = TasksManager::new();
+        let mut sync = GitSync::open(&first, &manager).unwrap();
+        for name in ["a", "b"] {
+            manager.add_task(Task::new(name.to_owned(), String::new(), Priority::Low));
+        }
+        sync.commit(&mut manager).unwrap();
+        sync.push().unwrap();
+
+        let second = clone(&root.0, "second");
+        (root, first, second)
+    }
+
+    #[test]
+    fn diverged_changes_merge_by_id() {
+        let (root, first, second) = setup();
+
+        let (mut manager, mut sync) = open(&first);
+        manager.tasks_mut()[0].description = "из первой копии".to_owned();
+        assert_eq!(sync.commit(&mut manager).unwrap().unwrap(), "Изменена задача \"a\" (description)");
+        assert!(matches!(sync.pull(&mut manager).unwrap(), Pull::UpToDate));
+        sync.push().unwrap();
+
+        let (mut manager, mut sync) = open(&second);
+        manager.tasks_mut()[1].priority = Priority::High;
+        manager.add_task(Task::new("c".to_owned(), String::new(), Priority::Low));
+        let merge = match sync.pull(&mut manager).unwrap() {
+            Pull::Merge(merge) => merge,
+            _ => panic!("ожидалось объединение"),
+        };
+        assert!(merge.conflicts.is_empty());
+        sync.finish(&mut manager, merge).unwrap();
+        sync.push().unwrap();
+
+        assert_eq!(manager.tasks()[0].description, "из первой копии");
+        assert!(manager.tasks()[1].priority == Priority::High);
+        assert_eq!(manager.tasks().len(), 3);
+        assert!(git(&root.0.join("second"), &["log", "--format=%B"]).contains("Добавлена задача \"c\""));
+
+        let (mut manager, mut sync) = open(&first);
+        assert!(matches!(sync.pull(&mut manager).unwrap(), Pull::FastForward));
+        assert_eq!(manager.tasks().len(), 3);
+    }
+
+    #[test]
+    fn conflicting_edits_are_resolved() {
+        let (_root, first, second) = setup();
+
+        let (mut manager, mut sync) = open(&first);
+        manager.tasks_mut()[0].description = "первая".to_owned();
+        sync.pull(&mut manager).unwrap();
+        sync.push().unwrap();
+
+        let (mut manager, mut sync) = open(&second);
+        manager.tasks_mut()[0].description = "вторая".to_owned();
+        manager.tasks_mut().remove(1);
+        let mut merge = match sync.pull(&mut manager).unwrap() {
+            Pull::Merge(merge) => merge,
+            _ => panic!("ожидалось объединение"),
+        };
+
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].fields, vec!["description"]);
+        assert!(merge.conflicts[0].describe().contains("description: у нас \"вторая\", у них \"первая\""));
+
+        merge.resolve(0, Side::Theirs);
+        sync.finish(&mut manager, merge).unwrap();
+        assert_eq!(manager.tasks().len(), 1);
+        assert_eq!(manager.tasks()[0].description, "первая");
+    }
+
+    #[test]
+    fn history_is_merged_and_deletion_conflicts_with_edit() {
+        let mut manager = TasksManager::new();
+        manager.add_task(Task::new("a".to_owned(), String::new(), Priority::Low));
+        manager.add_task(Task::new("b".to_owned(), String::new(), Priority::Low));
+        let base = to_value(&manager).unwrap();
+
+        let mut ours = TasksManager::from_json(base.clone()).unwrap();
+        ours.tasks_mut()[0].set_status("In Progress").unwrap();
+        ours.tasks_mut().remove(1);
+        let mut theirs = TasksManager::from_json(base.clone()).unwrap();
+        assert!(theirs.tasks_mut()[0].add_tag("дом"));
+        theirs.tasks_mut()[1].description = "изменена".to_owned();
+
+        let merge = Merge::new(&base, &to_value(&ours).unwrap(), &to_value(&theirs).unwrap());
+        assert_eq!(merge.conflicts.len(), 1);
+        assert!(merge.conflicts[0].describe().contains("у нас задача удалена, у них изменена"));
+
+        let merged = TasksManager::from_json(merge.into_value()).unwrap();
+        let task = &merged.tasks()[0];
+        assert_eq!(task.status, "In Progress");
+        assert_eq!(task.tags, vec!["дом"]);
+        assert_eq!(task.history.len(), 3);
+        assert_eq!(merged.tasks().len(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::Write;
+use terminal_size::{terminal_size, Width};
+use crate::config::{self, tr};
+use crate::Task;
+
+const DEFAULT_WIDTH: usize = 80;
+const MAX_NAME_WIDTH: usize = 30;
+const MIN_DESCRIPTION_WIDTH: usize = 10;
+const SEPARATOR: &str = " | ";
+
+pub fn terminal_width() -> usize {
+    if let Some((Width(width), _)) = terminal_size() {
+        return width as usize;
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub fn pad(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+struct Row {
+    cells: Vec<String>,
+    colors: Vec<&'static str>,
+}
+
+impl Task {
+    fn status_cell(&self) -> (String, &'static str) {
+        if self.is_overdue() {
+            (format!("{} ({})", self.status, tr("просрочено", "overdue")), config::current().colors.overdue)
+        } else {
+            (self.status.clone(), "")
+        }
+    }
+}
+
+pub fn print_table(out: &mut impl Write, tasks: &[(usize, &str, &Task)], show_project: bool) -> std::io::Result<()> {
+    if tasks.is_empty() {
+        writeln!(out, "{}", tr("Нет задач.", "No tasks."))?;
+        return Ok(());
+    }
+
+    let date_format = &config::current().date_format;
+    let mut header = vec![
+        "№".to_owned(),
+        tr("Проект", "Project").to_owned(),
+        tr("Название", "Name").to_owned(),
+        tr("Приоритет", "Priority").to_owned(),
+        tr("Статус", "Status").to_owned(),
+        tr("Дата", "Date").to_owned(),
+        tr("Описание", "Description").to_owned(),
+    ];
+
+    let mut rows: Vec<Row> = tasks
+        .iter()
+        .map(|(number, project, task)| {
+            let (status, status_color) = task.status_cell();
+            Row {
+                cells: vec![
+                    number.to_string(),
+                    truncate(project, MAX_NAME_WIDTH),
+                    truncate(&task.name, MAX_NAME_WIDTH),
+                    task.priority.to_string(),
+                    status,
+                    task.add_time.format(date_format).to_string(),
+                    task.description.replace('\n', " "),
+                ],
+                colors: vec!["", "", "", task.priority.color(), status_color, "", ""],
+            }
+        })
+        .collect();
+
+    if !show_project {
+        header.remove(1);
+        for row in rows.iter_mut() {
+            row.cells.remove(1);
+            row.colors.remove(1);
+        }
+    }
+
+    let fixed_columns = header.len() - 1;
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+    for row in &rows {
+        for (column, cell) in row.cells.iter().enumerate().take(fixed_columns) {
+            widths[column] = widths[column].max(cell.chars().count());
+        }
+    }
+
+    let used: usize = widths.iter().take(fixed_columns).sum::<usize>() + SEPARATOR.len() * fixed_columns;
+    widths[fixed_columns] = terminal_width().saturating_sub(used).max(MIN_DESCRIPTION_WIDTH);
+
+    let header: Vec<String> = header
+        .iter()
+        .zip(&widths)
+        .map(|(cell, width)| pad(&truncate(cell, *width), *width))
+        .collect();
+    writeln!(out, "{}", header.join(SEPARATOR).trim_end())?;
+    writeln!(out, "{}", "-".repeat(widths.iter().sum::<usize>() + SEPARATOR.len() * fixed_columns))?;
+
+    for row in rows {
+        let cells: Vec<String> = row.cells
+            .iter()
+            .zip(&widths)
+            .zip(&row.colors)
+            .map(|((cell, width), color)| config::paint(&pad(&truncate(cell, *width), *width), color))
+            .collect();
+        writeln!(out, "{}", cells.join(SEPARATOR).trim_end())?;
+    }
+
+    Ok(())
+}
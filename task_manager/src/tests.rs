@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::{bulk, crypto, ConsoleTask, Hooks, Priority, Task, TasksManager};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> Self {
+        let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+        Self(std::env::temp_dir().join(format!("task_manager_{}_{}_{}", std::process::id(), id, name)))
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn task(name: &str, priority: Priority) -> Task {
+    Task::new(name.to_owned(), format!("описание {}", name), priority)
+}
+
+fn manager_with(names: &[&str]) -> TasksManager {
+    let mut manager = TasksManager::new();
+    for name in names {
+        manager.add_task(task(name, Priority::Medium));
+    }
+    manager
+}
+
+fn run_script(console: &mut ConsoleTask<&[u8], Vec<u8>>) -> String {
+    let err = console.run().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    String::from_utf8(std::mem::take(&mut console.writer)).unwrap()
+}
+
+fn console(script: &str) -> ConsoleTask<&[u8], Vec<u8>> {
+    ConsoleTask::with_io(script.as_bytes(), vec![], Hooks::new(None))
+}
+
+#[test]
+fn add_and_find_task() {
+    let manager = manager_with(&["первая", "вторая"]);
+
+    assert_eq!(manager.tasks().len(), 2);
+    assert_eq!(manager.find_task("вторая"), Some(1));
+    assert_eq!(manager.find_task("третья"), None);
+}
+
+#[test]
+fn edit_task_updates_fields_and_history() {
+    let mut manager = manager_with(&["старое"]);
+
+    let msg = manager.edit_task("старое", task("новое", Priority::High)).unwrap();
+    assert_eq!(msg, "Задача \"старое\" обновлена успешно");
+
+    let edited = &manager.tasks()[0];
+    assert_eq!(edited.name, "новое");
+    assert_eq!(edited.description, "описание новое");
+    assert!(edited.priority == Priority::High);
+    assert_eq!(edited.history.len(), 4);
+
+    let err = manager.edit_task("старое", task("x", Priority::Low)).unwrap_err();
+    assert_eq!(err, "Задача с именем \"старое\" не существует");
+}
+
+#[test]
+fn remove_task() {
+    let mut manager = manager_with(&["a", "b"]);
+
+    assert_eq!(manager.remove_task("a").unwrap(), "Задача \"a\" удалена успешно");
+    assert_eq!(manager.tasks().len(), 1);
+    assert_eq!(manager.remove_task("a").unwrap_err(), "Задача с именем \"a\" не существует");
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let file = TempFile::new("round_trip.json");
+    let mut manager = manager_with(&["a", "b"]);
+    manager.tasks_mut()[1].mark_as_completed().unwrap();
+    manager.store_to_file(file.path(), None).unwrap();
+
+    let mut loaded = TasksManager::new();
+    loaded.read_from_file(file.path(), None).unwrap();
+
+    assert_eq!(loaded.tasks().len(), 2);
+    assert_eq!(loaded.tasks()[0].name, "a");
+    assert!(loaded.tasks()[1].is_completed());
+    assert!(loaded.tasks()[1].completed_time.is_some());
+}
+
+#[test]
+fn encrypted_save_requires_password() {
+    let file = TempFile::new("encrypted.json");
+    let mut manager = manager_with(&["секрет"]);
+    manager.store_to_file(file.path(), Some("пароль")).unwrap();
+
+    let mut loaded = TasksManager::new();
+    assert!(loaded.read_from_file(file.path(), None).unwrap_err().contains("требуется пароль"));
+    assert_eq!(loaded.read_from_file(file.path(), Some("другой")).unwrap_err(), "Неверный пароль");
+    loaded.read_from_file(file.path(), Some("пароль")).unwrap();
+    assert_eq!(loaded.tasks()[0].name, "секрет");
+}
+
+#[test]
+fn store_refuses_to_overwrite_foreign_file() {
+    let file = TempFile::new("foreign.json");
+    fs::write(file.path(), "[]").unwrap();
+
+    let mut manager = manager_with(&["a"]);
+    let err = manager.store_to_file(file.path(), None).unwrap_err();
+    assert!(err.contains("уже существует"));
+}
+
+#[test]
+fn load_missing_and_legacy_files() {
+    let mut manager = TasksManager::new();
+    let missing = TempFile::new("missing.json");
+    assert!(manager.read_from_file(missing.path(), None).unwrap_err().contains("не существует"));
+
+    let legacy = TempFile::new("legacy.json");
+    let tasks = serde_json::to_string(&vec![task("старая", Priority::Low)]).unwrap();
+    fs::write(legacy.path(), tasks).unwrap();
+    manager.read_from_file(legacy.path(), None).unwrap();
+    assert_eq!(manager.tasks()[0].name, "старая");
+}
+
+#[test]
+fn menu_add_list_and_find() {
+    let mut console = console("1\nкупить\nмолоко\n1\n5\n2\nкупть\n2\n1\n");
+    let output = run_script(&mut console);
+
+    assert!(output.starts_with("1. Добавить задачу\n"));
+    assert!(output.contains("Задача \"купить\" добавлена"));
+    assert!(output.contains("Проект \"Основной\":"));
+    assert!(output.contains("Описание: \"молоко\""));
+    assert!(output.contains("Задача с именем \"купть\" не существует. Возможно, вы имели в виду: \"купить\""));
+    assert!(output.contains("Задача найдена!"));
+}
+
+#[test]
+fn menu_quick_add_rejects_existing_name() {
+    let mut console = console("27\nкупить !high\n27\nкупить #дом\n");
+    let output = run_script(&mut console);
+
+    assert!(output.contains("Задача \"купить\" добавлена"));
+    assert!(output.contains("Задача с именем \"купить\" уже существует"));
+    assert_eq!(console.tasks_manager.tasks().len(), 1);
+}
+
+#[test]
+fn menu_add_and_rename_reject_existing_names() {
+    let mut console = console("1\na\n\n1\n1\nb\n\n1\n1\na\n\n1\n3\nb\na\n\n1\n");
+    let output = run_script(&mut console);
+
+    assert_eq!(output.matches("Задача с именем \"a\" уже существует").count(), 2);
+    let names: Vec<&str> = console.tasks_manager.tasks().iter().map(|task| task.name.as_str()).collect();
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn menu_all_projects_view_is_a_table_with_project_column() {
+    let mut console = console("18\n");
+    console.tasks_manager.add_task(task("первая", Priority::Low));
+    console.tasks_manager.create_project("Дом").unwrap();
+    console.tasks_manager.switch_project("Дом").unwrap();
+    console.tasks_manager.add_task(task("вторая", Priority::High));
+    let output = run_script(&mut console);
+
+    assert!(output.contains("№ | Проект"));
+    assert!(output.lines().any(|line| line.starts_with("1 | Основной | первая")));
+    assert!(output.lines().any(|line| line.starts_with("1 | Дом") && line.contains("| вторая")));
+    assert!(!output.contains("1. Проект:"));
+}
+
+#[test]
+fn menu_invalid_input() {
+    let mut console = console("99\n1\nзадача\n\nz\n4\nнет\n");
+    let output = run_script(&mut console);
+
+    assert!(output.contains("Неверный ввод команды"));
+    assert!(output.contains("Неправильный приоритет, изменено на Низкий"));
+    assert!(output.contains("Задача с именем \"нет\" не существует"));
+    assert_eq!(console.tasks_manager.tasks().len(), 1);
+}
+
+#[test]
+fn menu_edit_complete_and_remove_by_number() {
+    let mut console = console("3\n1\nновая\nописание\n2\n8\n1\n10\nновая\n4\n1\n");
+    console.tasks_manager.add_task(task("старая", Priority::Low));
+    let output = run_script(&mut console);
+
+    assert!(output.contains("Задача \"старая\" обновлена успешно"));
+    assert!(output.contains("Задача \"новая\" отмечена как выполненная"));
+    assert!(output.contains("Задача \"новая\" отмечена как невыполненная"));
+    assert!(output.contains("Задача \"новая\" удалена успешно"));
+    assert!(console.tasks_manager.tasks().is_empty());
+}
+
+#[test]
+fn menu_save_and_load() {
+    let file = TempFile::new("menu.json");
+    let script = format!("27\nзадача !high #дом\n6\n{0}\nн\n4\nзадача\n7\n{0}\n", file.path());
+    let mut console = console(&script);
+    let output = run_script(&mut console);
+
+    assert!(output.contains("Данные успешно сохранены"));
+    assert!(output.contains("Данные успешно считаны"));
+    let loaded = &console.tasks_manager.tasks()[0];
+    assert_eq!(loaded.name, "задача");
+    assert_eq!(loaded.tags, vec!["дом"]);
+}
+
+#[test]
+fn menu_load_error() {
+    let file = TempFile::new("absent.json");
+    let script = format!("7\n{}\n", file.path());
+    let mut console = console(&script);
+    let output = run_script(&mut console);
+
+    assert!(output.contains(&format!("Файл \"{}\" не существует", file.path())));
+}
+
+#[test]
+fn menu_bulk_operations() {
+    let mut console = console("26\n1-2\n3\n1\nд\n26\npriority:high\n2\nн\n26\n5\n");
+    for name in ["a", "b", "c"] {
+        console.tasks_manager.add_task(task(name, Priority::Low));
+    }
+    let output = run_script(&mut console);
+
+    assert!(output.contains("Задача \"a\" обновлена успешно"));
+    assert!(output.contains("Задача \"b\" обновлена успешно"));
+    assert!(output.contains("Операция отменена"));
+    assert!(output.contains("Неправильный номер или диапазон \"5\", всего задач: 3"));
+    let high = console.tasks_manager.tasks().iter().filter(|task| task.priority == Priority::High).count();
+    assert_eq!(high, 2);
+}
+
+#[test]
+fn archived_tasks_stay_in_report_and_timesheet() {
+    let mut manager = manager_with(&["a", "b"]);
+    manager.start_timer("a").unwrap();
+    manager.tasks_mut()[0].mark_as_completed().unwrap();
+    manager.archive_task("a").unwrap();
+
+    assert!(!manager.archive()[0].is_timer_running());
+    assert_eq!(manager.stop_timer().unwrap_err(), "Нет запущенного таймера");
+    let report = manager.report().to_text();
+    assert!(report.starts_with("Всего задач: 2\nВыполнено: 1\n"));
+    assert!(report.contains("\nПо статусам:\n  Todo: 1\n  In Progress: 0\n  Review: 0\n  Done: 1\n"));
+    assert!(manager.timesheet().to_text().contains("Основной / a"));
+}
+
+#[test]
+fn bulk_operations_target_tasks_by_id() {
+    let mut manager = manager_with(&["a"]);
+    manager.tasks_mut().push(task("a", Priority::Low));
+    let first = manager.tasks()[0].id.clone();
+    let second = manager.tasks()[1].id.clone();
+    let hooks = Hooks::new(None);
+
+    let results = manager.apply_bulk(&hooks, std::slice::from_ref(&second), &bulk::BulkAction::SetPriority(Priority::High));
+    assert!(results[0].is_ok());
+    assert_eq!(manager.tasks()[1].id, second);
+    assert!(manager.tasks()[1].priority == Priority::High);
+    assert_eq!(manager.tasks()[1].history.len(), 2);
+    assert!(manager.tasks()[0].priority == Priority::Medium);
+
+    let results = manager.apply_bulk(&hooks, &[second], &bulk::BulkAction::Delete);
+    assert!(results[0].is_ok());
+    assert_eq!(manager.tasks().len(), 1);
+    assert_eq!(manager.tasks()[0].id, first);
+}
+
+#[test]
+fn merge_keeps_notes_and_time_added_in_other_copy() {
+    let file = TempFile::new("merge_notes.json");
+    let mut local = manager_with(&["a"]);
+    let mut other = TasksManager::new();
+    other.add_task(local.tasks()[0].clone());
+    other.tasks_mut()[0].add_note("из другой копии").unwrap();
+    other.start_timer("a").unwrap();
+    other.stop_timer().unwrap();
+    other.store_to_file(file.path(), None).unwrap();
+
+    local.merge_from_file(file.path(), None).unwrap();
+    assert_eq!(local.tasks()[0].notes.len(), 1);
+    assert_eq!(local.tasks()[0].time_entries.len(), 1);
+}
+
+#[test]
+fn menu_keeps_encryption_of_loaded_file() {
+    let file = TempFile::new("menu_encrypted.json");
+    let mut manager = manager_with(&["секрет"]);
+    manager.store_to_file(file.path(), Some("пароль")).unwrap();
+
+    let script = format!("7\n{0}\nпароль\n6\n{0}\nн\n", file.path());
+    let output = run_script(&mut console(&script));
+    assert!(output.contains("Файл был зашифрован. Сохранить его без шифрования? (д/н): Данные успешно сохранены"));
+    assert!(crypto::is_encrypted_file(file.path()));
+
+    let script = format!("7\n{0}\nпароль\n6\n{0}\nд\n", file.path());
+    run_script(&mut console(&script));
+    assert!(!crypto::is_encrypted_file(file.path()));
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::config::{self, tr, trf};
+use crate::history::HistoryEvent;
+use crate::report::format_duration;
+use crate::{Task, TasksManager};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimeEntry {
+    start: DateTime<Local>,
+    end: Option<DateTime<Local>>,
+}
+
+impl TimeEntry {
+    fn duration(&self) -> Duration {
+        self.end.unwrap_or_else(Local::now) - self.start
+    }
+
+    fn split_by_day(&self) -> Vec<(NaiveDate, Duration)> {
+        let end = self.end.unwrap_or_else(Local::now);
+        let mut parts = vec![];
+        let mut start = self.start;
+
+        while start.date_naive() < end.date_naive() {
+            let next_day = start.date_naive().succ_opt().and_then(|day| day.and_hms_opt(0, 0, 0));
+            let midnight = match next_day.and_then(|day| day.and_local_timezone(Local).earliest()) {
+                Some(midnight) => midnight,
+                None => break,
+            };
+            parts.push((start.date_naive(), midnight - start));
+            start = midnight;
+        }
+
+        parts.push((start.date_naive(), end - start));
+        parts
+    }
+}
+
+impl Task {
+    pub fn is_timer_running(&self) -> bool {
+        self.time_entries.iter().any(|entry| entry.end.is_none())
+    }
+
+    pub fn tracked_time(&self) -> Duration {
+        self.time_entries.iter().map(TimeEntry::duration).fold(Duration::zero(), |sum, duration| sum + duration)
+    }
+
+    pub fn stop_timer(&mut self) {
+        if !self.is_timer_running() {
+            return;
+        }
+
+        for entry in self.time_entries.iter_mut().filter(|entry| entry.end.is_none()) {
+            entry.end = Some(Local::now());
+        }
+        self.record(HistoryEvent::TimerStopped);
+    }
+}
+
+pub struct Timesheet {
+    per_day: BTreeMap<NaiveDate, Duration>,
+    per_task: Vec<(String, Duration)>,
+}
+
+impl TasksManager {
+    pub fn start_timer(&mut self, name: &str) -> Result<String, String> {
+        let index = match self.find_task(name) {
+            Some(index) => index,
+            None => return Err(trf!("Задача с именем \"{}\" не существует", "Task \"{}\" does not exist", name)),
+        };
+
+        if self.tasks()[index].is_timer_running() {
+            return Err(trf!("Таймер задачи \"{}\" уже запущен", "Timer of task \"{}\" is already running", name));
+        }
+
+        let mut message = String::new();
+        if let Ok(stopped) = self.stop_timer() {
+            message.push_str(&stopped);
+            message.push('\n');
+        }
+
+        let task = &mut self.tasks_mut()[index];
+        task.time_entries.push(TimeEntry { start: Local::now(), end: None });
+        task.record(HistoryEvent::TimerStarted);
+        message.push_str(&trf!("Таймер задачи \"{}\" запущен", "Timer of task \"{}\" started", name));
+        Ok(message)
+    }
+
+    pub fn stop_timer(&mut self) -> Result<String, String> {
+        let mut tasks = self.projects.iter_mut().flat_map(|project| project.tasks.iter_mut());
+
+        match tasks.find(|task| task.is_timer_running()) {
+            Some(task) => {
+                task.stop_timer();
+                Ok(trf!(
+                    "Таймер задачи \"{}\" остановлен, всего затрачено: {}",
+                    "Timer of task \"{}\" stopped, total time spent: {}",
+                    task.name,
+                    format_duration(task.tracked_time())
+                ))
+            }
+            None => Err(tr("Нет запущенного таймера", "No timer is running").to_owned()),
+        }
+    }
+
+    pub fn timesheet(&self) -> Timesheet {
+        let mut per_day = BTreeMap::new();
+        let mut per_task = vec![];
+
+        for project in &self.projects {
+            for task in project.tasks.iter().chain(&project.archive).filter(|task| !task.time_entries.is_empty()) {
+                for entry in &task.time_entries {
+                    for (day, duration) in entry.split_by_day() {
+                        let total = per_day.entry(day).or_insert_with(Duration::zero);
+                        *total += duration;
+                    }
+                }
+                per_task.push((format!("{} / {}", project.name, task.name), task.tracked_time()));
+            }
+        }
+
+        Timesheet { per_day, per_task }
+    }
+}
+
+impl Timesheet {
+    pub fn to_text(&self) -> String {
+        if self.per_task.is_empty() {
+            return tr("Нет учтённого времени.\n", "No tracked time.\n").to_owned();
+        }
+
+        let mut text = tr("По дням:\n", "By day:\n").to_owned();
+        for (day, duration) in &self.per_day {
+            text.push_str(&format!("  {}: {}\n", config::current().format_day(*day), format_duration(*duration)));
+        }
+
+        text.push_str(tr("\nПо задачам:\n", "\nBy task:\n"));
+        for (name, duration) in &self.per_task {
+            text.push_str(&format!("  {}: {}\n", name, format_duration(*duration)));
+        }
+
+        let total = self.per_task.iter().fold(Duration::zero(), |sum, (_, duration)| sum + *duration);
+        text.push_str(&trf!("\nИтого: {}\n", "\nTotal: {}\n", format_duration(total)));
+        text
+    }
+}
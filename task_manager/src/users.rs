@@ -0,0 +1,95 @@
+use crate::config::{self, trf};
+use crate::history::HistoryEvent;
+use crate::{sort_for_display, Task, TasksManager};
+
+pub fn current_user() -> Option<&'static str> {
+    config::current().user.as_deref()
+}
+
+impl Task {
+    pub fn is_assigned_to(&self, user: &str) -> bool {
+        self.assignee.as_deref().is_some_and(|assignee| assignee.eq_ignore_ascii_case(user))
+    }
+
+    pub fn assign(&mut self, assignee: Option<String>) -> Result<(), String> {
+        if self.assignee == assignee {
+            return Err(match assignee {
+                Some(assignee) => trf!("Задача \"{}\" уже назначена на {}", "Task \"{}\" is already assigned to {}", self.name, assignee),
+                None => trf!("У задачи \"{}\" нет исполнителя", "Task \"{}\" has no assignee", self.name),
+            });
+        }
+
+        let old = std::mem::replace(&mut self.assignee, assignee);
+        self.record(HistoryEvent::Assigned { old, new: self.assignee.clone() });
+        Ok(())
+    }
+}
+
+impl TasksManager {
+    pub fn assigned_tasks(&self, user: &str) -> Vec<(usize, &str, &Task)> {
+        let mut tasks = vec![];
+
+        for project in &self.projects {
+            for (index, task) in sort_for_display(&project.tasks).into_iter().enumerate() {
+                if task.is_assigned_to(user) && !task.is_closed() {
+                    tasks.push((index + 1, project.name.as_str(), task));
+                }
+            }
+        }
+
+        tasks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::Filter;
+    use crate::{Priority, Task, TasksManager};
+
+    fn task(name: &str, assignee: Option<&str>) -> Task {
+        let mut task = Task::new(name.to_owned(), String::new(), Priority::Low);
+        task.assignee = assignee.map(str::to_owned);
+        task
+    }
+
+    #[test]
+    fn assign_records_history() {
+        let mut task = task("a", None);
+        let history = task.history.len();
+
+        task.assign(Some("anna".to_owned())).unwrap();
+        task.assign(Some("oleg".to_owned())).unwrap();
+        task.assign(None).unwrap();
+
+        assert_eq!(task.history.len(), history + 3);
+        assert_eq!(task.history.last().unwrap().event.to_string(), "Исполнитель изменён: oleg -> не назначен");
+        assert_eq!(task.assign(None).unwrap_err(), "У задачи \"a\" нет исполнителя");
+    }
+
+    #[test]
+    fn assigned_tasks_skip_closed_and_other_users() {
+        let mut manager = TasksManager::new();
+        manager.add_task(task("моя", Some("Anna")));
+        manager.add_task(task("чужая", Some("oleg")));
+        let mut done = task("готовая", Some("anna"));
+        done.mark_as_completed().unwrap();
+        manager.add_task(done);
+
+        let names: Vec<&str> = manager.assigned_tasks("anna").iter().map(|(_, _, task)| task.name.as_str()).collect();
+        assert_eq!(names, vec!["моя"]);
+    }
+
+    #[test]
+    fn filter_by_assignee() {
+        let anna = task("a", Some("anna"));
+        let nobody = task("b", None);
+
+        let filter = Filter::parse("@anna").unwrap();
+        assert!(filter.matches(&anna) && !filter.matches(&nobody));
+
+        let filter = Filter::parse("assignee:none").unwrap();
+        assert!(!filter.matches(&anna) && filter.matches(&nobody));
+
+        assert!(Filter::parse("@").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::io::Write;
+use chrono::Local;
+use serde::{Deserialize, Deserializer};
+use crate::config::{self, tr, trf};
+use crate::history::HistoryEvent;
+use crate::table::{pad, terminal_width, truncate};
+use crate::{sort_for_display, Task, TasksManager};
+
+const MIN_COLUMN_WIDTH: usize = 12;
+const COLUMN_SEPARATOR: &str = " | ";
+
+pub struct Workflow {
+    pub states: Vec<String>,
+    pub initial: String,
+    pub done: String,
+    pub closed: Vec<String>,
+    transitions: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RawWorkflow {
+    states: Option<Vec<String>>,
+    initial: Option<String>,
+    done: Option<String>,
+    closed: Option<Vec<String>>,
+    transitions: Option<HashMap<String, Vec<String>>>,
+}
+
+impl Workflow {
+    pub fn from_raw(raw: RawWorkflow) -> Result<Self, String> {
+        let custom_states = raw.states.is_some();
+        let states = raw.states.unwrap_or_else(|| {
+            ["Todo", "In Progress", "Review", "Done", "Cancelled"].map(str::to_owned).to_vec()
+        });
+
+        if states.is_empty() {
+            return Err("Список статусов workflow не может быть пустым".to_owned());
+        }
+
+        let transitions = match raw.transitions {
+            Some(transitions) => Some(transitions),
+            None if custom_states => None,
+            None => Some(HashMap::from(
+                [
+                    ("Todo", vec!["In Progress", "Done", "Cancelled"]),
+                    ("In Progress", vec!["Todo", "Review", "Done", "Cancelled"]),
+                    ("Review", vec!["In Progress", "Done", "Cancelled"]),
+                    ("Done", vec!["Todo"]),
+                    ("Cancelled", vec!["Todo"]),
+                ]
+                .map(|(from, to)| (from.to_owned(), to.into_iter().map(str::to_owned).collect())),
+            )),
+        };
+
+        let workflow = Self {
+            initial: raw.initial.unwrap_or_else(|| states[0].clone()),
+            done: raw.done.unwrap_or_else(|| if custom_states { states[states.len() - 1].clone() } else { "Done".to_owned() }),
+            closed: raw.closed.unwrap_or_else(|| if custom_states { vec![] } else { vec!["Cancelled".to_owned()] }),
+            states,
+            transitions,
+        };
+
+        let mut mentioned = vec![&workflow.initial, &workflow.done];
+        mentioned.extend(&workflow.closed);
+        if let Some(transitions) = &workflow.transitions {
+            for (from, to) in transitions {
+                mentioned.push(from);
+                mentioned.extend(to);
+            }
+        }
+
+        match mentioned.into_iter().find(|state| !workflow.states.contains(state)) {
+            Some(state) => Err(format!("Статус \"{}\" не объявлен в списке статусов workflow", state)),
+            None => Ok(workflow),
+        }
+    }
+
+    pub fn find_state(&self, input: &str) -> Option<&str> {
+        if let Ok(number) = input.parse::<usize>() {
+            return number.checked_sub(1).and_then(|index| self.states.get(index)).map(String::as_str);
+        }
+
+        let normalize = |name: &str| name.to_lowercase().replace(' ', "_");
+        self.states.iter().find(|state| normalize(state) == normalize(input)).map(String::as_str)
+    }
+
+    pub fn is_closed(&self, status: &str) -> bool {
+        status == self.done || self.closed.iter().any(|state| state == status)
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        if from == to || !self.states.iter().any(|state| state == to) {
+            return false;
+        }
+
+        match &self.transitions {
+            Some(transitions) => transitions.get(from).is_some_and(|allowed| allowed.iter().any(|state| state == to)),
+            None => true,
+        }
+    }
+
+    pub fn next_states(&self, from: &str) -> Vec<&str> {
+        self.states
+            .iter()
+            .filter(|state| self.can_transition(from, state))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+pub fn initial_status() -> String {
+    config::current().workflow.initial.clone()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredStatus {
+    Legacy(bool),
+    Name(String),
+}
+
+pub fn deserialize_status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let workflow = &config::current().workflow;
+    Ok(match StoredStatus::deserialize(deserializer)? {
+        StoredStatus::Legacy(true) => workflow.done.clone(),
+        StoredStatus::Legacy(false) => workflow.initial.clone(),
+        StoredStatus::Name(name) => name,
+    })
+}
+
+impl Task {
+    pub fn is_completed(&self) -> bool {
+        self.status == config::current().workflow.done
+    }
+
+    pub fn is_closed(&self) -> bool {
+        config::current().workflow.is_closed(&self.status)
+    }
+
+    pub fn set_status(&mut self, status: &str) -> Result<(), String> {
+        let workflow = &config::current().workflow;
+        if self.status == status {
+            return Err(trf!("Задача \"{}\" уже в статусе \"{}\"", "Task \"{}\" is already in status \"{}\"", self.name, status));
+        }
+        if !workflow.can_transition(&self.status, status) {
+            return Err(trf!(
+                "Переход задачи \"{}\" из статуса \"{}\" в \"{}\" не разрешён",
+                "Transition of task \"{}\" from status \"{}\" to \"{}\" is not allowed",
+                self.name, self.status, status
+            ));
+        }
+
+        match (self.is_closed(), workflow.is_closed(status)) {
+            (false, true) => self.completed_time = Some(Local::now()),
+            (true, false) => self.completed_time = None,
+            _ => {}
+        }
+
+        let old = std::mem::replace(&mut self.status, status.to_owned());
+        self.record(HistoryEvent::StatusChanged { old, new: self.status.clone() });
+        Ok(())
+    }
+}
+
+impl TasksManager {
+    pub fn print_board(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut states: Vec<&str> = config::current().workflow.states.iter().map(String::as_str).collect();
+        let mut columns: Vec<Vec<&Task>> = states
+            .iter()
+            .map(|state| sort_for_display(self.tasks()).into_iter().filter(|task| task.status == *state).collect())
+            .collect();
+
+        let other: Vec<&Task> = sort_for_display(self.tasks()).into_iter().filter(|task| !states.contains(&task.status.as_str())).collect();
+        if !other.is_empty() {
+            states.push(tr("Другие", "Other"));
+            columns.push(other);
+        }
+
+        let separators = COLUMN_SEPARATOR.len() * (states.len() - 1);
+        let width = (terminal_width().saturating_sub(separators) / states.len()).max(MIN_COLUMN_WIDTH);
+
+        writeln!(out, "{}: {}", tr("Проект", "Project"), self.current_project())?;
+        let header: Vec<String> = states
+            .iter()
+            .zip(&columns)
+            .map(|(state, tasks)| pad(&truncate(&format!("{} ({})", state, tasks.len()), width), width))
+            .collect();
+        writeln!(out, "{}", header.join(COLUMN_SEPARATOR).trim_end())?;
+        writeln!(out, "{}", "-".repeat(width * states.len() + separators))?;
+
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|tasks| match tasks.get(row) {
+                    Some(task) => {
+                        let color = if task.is_overdue() { config::current().colors.overdue } else { task.priority.color() };
+                        config::paint(&pad(&truncate(&task.name, width), width), color)
+                    }
+                    None => " ".repeat(width),
+                })
+                .collect();
+            writeln!(out, "{}", cells.join(COLUMN_SEPARATOR).trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Priority;
+
+    fn workflow(toml: &str) -> Result<Workflow, String> {
+        Workflow::from_raw(toml::from_str(toml).unwrap())
+    }
+
+    #[test]
+    fn default_workflow_transitions() {
+        let workflow = workflow("").unwrap();
+        assert_eq!(workflow.initial, "Todo");
+        assert!(workflow.can_transition("Todo", "In Progress"));
+        assert!(workflow.can_transition("Review", "Done"));
+        assert!(!workflow.can_transition("Done", "Review"));
+        assert!(!workflow.can_transition("Todo", "Todo"));
+        assert!(workflow.is_closed("Cancelled"));
+        assert_eq!(workflow.next_states("Done"), vec!["Todo"]);
+        assert_eq!(workflow.find_state("in_progress"), Some("In Progress"));
+        assert_eq!(workflow.find_state("2"), Some("In Progress"));
+    }
+
+    #[test]
+    fn custom_workflow_allows_any_transition_without_table() {
+        let workflow = workflow("states = [\"Новая\", \"В работе\", \"Готово\"]").unwrap();
+        assert_eq!(workflow.initial, "Новая");
+        assert_eq!(workflow.done, "Готово");
+        assert!(workflow.can_transition("Готово", "Новая"));
+    }
+
+    #[test]
+    fn rejects_unknown_states() {
+        let err = workflow("states = [\"A\", \"B\"]\n[transitions]\nA = [\"C\"]").err().unwrap();
+        assert_eq!(err, "Статус \"C\" не объявлен в списке статусов workflow");
+    }
+
+    #[test]
+    fn legacy_completed_flag_maps_to_states() {
+        let task = Task::new("a".to_owned(), String::new(), Priority::Low);
+        let mut json = serde_json::to_value(&task).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("status");
+
+        object.insert("completed".to_owned(), true.into());
+        let done: Task = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(done.status, "Done");
+
+        json["completed"] = false.into();
+        let todo: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(todo.status, "Todo");
+    }
+
+    #[test]
+    fn set_status_checks_transitions_and_tracks_completion() {
+        let mut task = Task::new("a".to_owned(), String::new(), Priority::Low);
+
+        assert!(task.set_status("Review").unwrap_err().contains("не разрешён"));
+        task.set_status("In Progress").unwrap();
+        task.set_status("Done").unwrap();
+        assert!(task.is_completed());
+        assert!(task.completed_time.is_some());
+
+        task.reopen().unwrap();
+        assert_eq!(task.status, "Todo");
+        assert!(task.completed_time.is_none());
+
+        task.set_status("In Progress").unwrap();
+        assert_eq!(task.reopen().unwrap_err(), "Задача \"a\" ещё не выполнена");
+    }
+
+    #[test]
+    fn board_shows_columns() {
+        let mut manager = TasksManager::new();
+        let mut task = Task::new("в работе".to_owned(), String::new(), Priority::Low);
+        task.set_status("In Progress").unwrap();
+        manager.add_task(task);
+        manager.add_task(Task::new("новая".to_owned(), String::new(), Priority::Low));
+        let mut task = Task::new("старая".to_owned(), String::new(), Priority::Low);
+        task.status = "Backlog".to_owned();
+        manager.add_task(task);
+
+        let mut out = vec![];
+        manager.print_board(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert!(lines[1].starts_with("Todo (1)"));
+        assert!(lines[1].contains("| In Progr"));
+        assert!(lines[3].starts_with("новая"));
+        assert!(lines[3].contains("в работе"));
+        assert!(lines[1].ends_with("Другие (1)"));
+        assert!(lines[3].ends_with("старая"));
+    }
+}